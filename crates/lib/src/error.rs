@@ -0,0 +1,22 @@
+use std::fmt::Display;
+
+use crate::types::Pos;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError {
+    /// Field is empty or too large to fit into coordinates
+    InvalidSize(Pos),
+    /// Snake at start does not fit into one line of field
+    InvalidLength(usize),
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(f, "invalid field size: {size}"),
+            Self::InvalidLength(len) => write!(f, "invalid snake length: {len}"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
@@ -1,8 +1,8 @@
 use std::{cell::RefCell, collections::VecDeque};
 
-use rand::random;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::types::*;
+use crate::{error::GameError, types::*};
 
 /// Game state
 ///
@@ -18,20 +18,93 @@ pub struct Game {
     direction: RefCell<MoveTo>,
     stats: RefCell<Stats>,
     last_event: RefCell<Option<GameEvent>>,
+    rng: RefCell<GameRng>,
+    /// Seed used for food placement
+    seed: u64,
+    /// Cells added for every food
+    growth: usize,
+    /// Ticks between eating and growing
+    growth_delay: usize,
+    /// Growth of every eaten food: tick from which tail stops, and cells which
+    /// are still to be added
+    growing: RefCell<VecDeque<(usize, usize)>>,
+    walls_kill: bool,
+}
+
+/// Settings of a single game
+#[derive(Debug, Clone)]
+pub struct GameOptions {
+    /// Seed for food placement. Random if not set, see [`Game::seed`]
+    pub seed: Option<u64>,
+    /// Snake's cells at start, in a line behind the head
+    pub initial_length: usize,
+    /// Direction of snake at start
+    pub direction: MoveTo,
+    /// Cells added to snake for every eaten food. Tail stops for this many
+    /// ticks
+    pub growth: usize,
+    /// Ticks after eating before tail stops. With 0 snake grows right away
+    pub growth_delay: usize,
+    /// Snake dies on field's border, instead of going through it
+    pub walls_kill: bool,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            initial_length: 1,
+            direction: MoveTo::default(),
+            growth: 1,
+            growth_delay: 0,
+            walls_kill: false,
+        }
+    }
 }
 
 impl Game {
-    pub fn new(size: Pos) -> Self {
+    /// Create new game. Field should have at least one cell, and its area should
+    /// fit into [`CoordType`]
+    pub fn new(size: Pos) -> Result<Self, GameError> {
+        Self::with_options(size, GameOptions::default())
+    }
+    /// Create new game with custom settings. See [`Game::new`]
+    pub fn with_options(size: Pos, options: GameOptions) -> Result<Self, GameError> {
+        if !size.is_valid_size() {
+            return Err(GameError::InvalidSize(size));
+        }
+        // snake is in one line, and there is a cell for food
+        let length = options.initial_length;
+        let line = match options.direction {
+            MoveTo::Left | MoveTo::Right => size.x,
+            MoveTo::Up | MoveTo::Down => size.y,
+        };
+        if length == 0 || length > line.0 as usize || length >= size.area() as usize {
+            return Err(GameError::InvalidLength(length));
+        }
         let mut snake = VecDeque::with_capacity(size.x.0.saturating_mul(size.y.0) as usize);
-        snake.push_back((size.x / 2, size.y / 2).into());
+        let mut pos: Pos = (size.x / 2, size.y / 2).into();
+        snake.push_back(pos);
+        // tail goes first
+        for _ in 1..length {
+            pos = pos.moved(options.direction.opposite(), size);
+            snake.push_front(pos);
+        }
+        let seed = options.seed.unwrap_or_else(rand::random);
         let s = Self {
             size,
             snake: RefCell::new(snake),
             last_event: RefCell::new(Some(GameEvent::GameStart)),
+            rng: RefCell::new(GameRng::seeded(seed)),
+            seed,
+            direction: RefCell::new(options.direction),
+            growth: options.growth,
+            growth_delay: options.growth_delay,
+            walls_kill: options.walls_kill,
             ..Default::default()
         };
         s.update_food();
-        s
+        Ok(s)
     }
     /// Move snake
     pub fn move_snake(&self) {
@@ -40,7 +113,7 @@ impl Game {
     /// Rotate snake to new direction
     pub fn rotate_to(&self, to: MoveTo) {
         // do not move back if snake has > 1 cells
-        if self.direction() == to.opposite() && self.stats().score > 0 {
+        if self.direction() == to.opposite() && self.snake.borrow().len() > 1 {
             return;
         }
         self.set_direction(to);
@@ -51,20 +124,37 @@ impl Game {
             return;
         }
 
+        self.stats.borrow_mut().ticks += 1;
+        if self.walls_kill && self.hits_wall(to) {
+            self.stats.borrow_mut().death = Some(DeathCause::Wall);
+            self.set_status(GameStatus::Fail);
+            self.set_event(GameEvent::Fail);
+            return;
+        }
         let next = self.get_next_pos(to);
         if self.is_in_snake(next) {
+            self.stats.borrow_mut().death = Some(DeathCause::SelfCollision);
             self.set_status(GameStatus::Fail);
             self.set_event(GameEvent::Fail);
             return;
         }
         if next == self.food() {
-            self.grow_to_pos(next);
+            // eating last food is reported as win
             self.set_event(GameEvent::FoodEat);
+            self.eat_food(next);
         } else {
             self.move_to_pos(next);
         }
     }
 
+    /// Field's size
+    pub fn size(&self) -> Pos {
+        self.size
+    }
+    /// Seed for food placement, can be used to replay the game
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
     pub fn snake(&self) -> Vec<Pos> {
         // todo: optimize clone
         self.snake.borrow().to_owned().into()
@@ -101,14 +191,31 @@ impl Game {
         self.snake.borrow().contains(&pos)
     }
 
-    /// Move snake to position
+    /// Move snake to position. Tail stays in place while snake grows
     fn move_to_pos(&self, to: Pos) {
         self.snake.borrow_mut().push_back(to);
-        self.snake.borrow_mut().pop_front();
+        let tick = self.stats.borrow().ticks;
+        let mut growing = self.growing.borrow_mut();
+        match growing.front_mut() {
+            Some((from, cells)) if *from <= tick => {
+                *cells -= 1;
+                if *cells == 0 {
+                    growing.pop_front();
+                }
+            }
+            _ => {
+                self.snake.borrow_mut().pop_front();
+            }
+        }
     }
-    /// Move snake to position and increase snake length
-    fn grow_to_pos(&self, to: Pos) {
-        self.snake.borrow_mut().push_back(to);
+    /// Move snake to food and schedule growing
+    fn eat_food(&self, to: Pos) {
+        if self.growth > 0 {
+            let tick = self.stats.borrow().ticks;
+            let from = tick + self.growth_delay;
+            self.growing.borrow_mut().push_back((from, self.growth));
+        }
+        self.move_to_pos(to);
         self.add_score();
         self.update_food();
     }
@@ -136,23 +243,28 @@ impl Game {
         *self.last_event.borrow_mut() = Some(event)
     }
 
+    /// Check if moving from head leaves the field
+    fn hits_wall(&self, to: MoveTo) -> bool {
+        let (x, y) = self.head().into();
+        let (w, h) = self.size.into();
+        match to {
+            MoveTo::Left => x == 0,
+            MoveTo::Right => x + 1 == w,
+            MoveTo::Up => y == 0,
+            MoveTo::Down => y + 1 == h,
+        }
+    }
     /// Calculate next position depending on field size and direction
     fn get_next_pos(&self, to: MoveTo) -> Pos {
-        let (x, y) = (self.size.x.0, self.size.y.0);
-        let shift = match to {
-            MoveTo::Left => (x - 1, 0),
-            MoveTo::Right => (x + 1, 0),
-            MoveTo::Up => (0, y - 1),
-            MoveTo::Down => (0, y + 1),
-        };
-        self.head().wrapping_add(shift.into(), self.size)
+        self.head().moved(to, self.size)
     }
     /// Generate random position for food
     fn get_new_food(&self) -> Pos {
         let size = self.size;
+        let mut rng = self.rng.borrow_mut();
         loop {
-            let x = random::<CoordType>() % size.x.0;
-            let y = random::<CoordType>() % size.y.0;
+            let x = rng.0.gen_range(0..size.x.0);
+            let y = rng.0.gen_range(0..size.y.0);
             let food = (x, y).into();
             if !self.is_in_snake(food) {
                 return food;
@@ -166,6 +278,21 @@ impl Game {
     }
 }
 
+#[derive(Debug)]
+struct GameRng(StdRng);
+
+impl GameRng {
+    fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum GameStatus {
     #[default]
@@ -183,9 +310,226 @@ pub enum GameEvent {
     Win,
 }
 
+/// Why game was lost
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    /// Snake bumped into itself
+    SelfCollision,
+    /// Snake hit field's border
+    Wall,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Stats {
     /// Count of eaten food
     pub score: usize,
     pub status: GameStatus,
+    /// Count of snake moves
+    pub ticks: usize,
+    /// Set when status is [`GameStatus::Fail`]
+    pub death: Option<DeathCause>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Pos = Pos::new(10, 6);
+    /// Cell outside of snake's row, food is kept there to not be eaten
+    const FAR: Pos = Pos::new(0, 0);
+
+    fn game(options: GameOptions) -> Game {
+        let options = GameOptions {
+            seed: Some(0),
+            ..options
+        };
+        Game::with_options(SIZE, options).unwrap()
+    }
+    /// Put food in front of the snake and move to it
+    fn eat(game: &Game) {
+        *game.food.borrow_mut() = game.head().moved(game.direction(), game.size);
+        game.move_snake();
+        assert_eq!(game.last_event(), Some(GameEvent::FoodEat));
+    }
+    /// Move without eating
+    fn step(game: &Game) {
+        *game.food.borrow_mut() = FAR;
+        game.move_snake();
+    }
+
+    #[test]
+    fn default_snake_is_single_cell() {
+        let game = game(GameOptions::default());
+        assert_eq!(game.snake(), [Pos::new(5, 3)]);
+        assert_eq!(game.direction(), MoveTo::Right);
+    }
+
+    #[test]
+    fn initial_snake_is_behind_head() {
+        let right = game(GameOptions {
+            initial_length: 3,
+            ..Default::default()
+        });
+        let cells = [Pos::new(3, 3), Pos::new(4, 3), Pos::new(5, 3)];
+        assert_eq!(right.snake(), cells);
+
+        let up = game(GameOptions {
+            initial_length: 3,
+            direction: MoveTo::Up,
+            ..Default::default()
+        });
+        assert_eq!(up.snake(), [Pos::new(5, 5), Pos::new(5, 4), Pos::new(5, 3)]);
+        assert_eq!(up.direction(), MoveTo::Up);
+    }
+
+    #[test]
+    fn initial_snake_should_fit_into_line() {
+        let with = |initial_length, direction| {
+            let options = GameOptions {
+                initial_length,
+                direction,
+                ..Default::default()
+            };
+            Game::with_options(SIZE, options).map(|_| ())
+        };
+        assert_eq!(with(0, MoveTo::Right), Err(GameError::InvalidLength(0)));
+        assert_eq!(with(10, MoveTo::Left), Ok(()));
+        assert_eq!(with(11, MoveTo::Left), Err(GameError::InvalidLength(11)));
+        assert_eq!(with(6, MoveTo::Down), Ok(()));
+        assert_eq!(with(7, MoveTo::Down), Err(GameError::InvalidLength(7)));
+        // no cell for food
+        let options = GameOptions {
+            initial_length: 3,
+            ..Default::default()
+        };
+        let err = Game::with_options(Pos::new(3, 1), options).map(|_| ());
+        assert_eq!(err, Err(GameError::InvalidLength(3)));
+    }
+
+    #[test]
+    fn snake_grows_by_one_cell_by_default() {
+        let game = game(GameOptions::default());
+        eat(&game);
+        assert_eq!(game.snake().len(), 2);
+        assert_eq!(game.stats().score, 1);
+        step(&game);
+        assert_eq!(game.snake().len(), 2);
+    }
+
+    #[test]
+    fn tail_stops_while_snake_grows() {
+        let game = game(GameOptions {
+            growth: 3,
+            ..Default::default()
+        });
+        eat(&game);
+        let tail = game.snake()[0];
+        assert_eq!(game.snake().len(), 2);
+        step(&game);
+        step(&game);
+        assert_eq!(game.snake().len(), 4);
+        assert_eq!(game.snake()[0], tail);
+        step(&game);
+        assert_eq!(game.snake().len(), 4);
+        assert_ne!(game.snake()[0], tail);
+    }
+
+    #[test]
+    fn growth_can_be_delayed() {
+        let game = game(GameOptions {
+            growth: 2,
+            growth_delay: 2,
+            ..Default::default()
+        });
+        eat(&game);
+        step(&game);
+        assert_eq!(game.snake().len(), 1);
+        step(&game);
+        step(&game);
+        assert_eq!(game.snake().len(), 3);
+        step(&game);
+        assert_eq!(game.snake().len(), 3);
+        assert_eq!(game.stats().score, 1);
+    }
+
+    #[test]
+    fn growth_of_several_food_adds_up() {
+        let game = game(GameOptions {
+            growth: 2,
+            ..Default::default()
+        });
+        eat(&game);
+        eat(&game);
+        for _ in 0..5 {
+            step(&game);
+        }
+        assert_eq!(game.snake().len(), 5);
+    }
+
+    #[test]
+    fn no_growth() {
+        let game = game(GameOptions {
+            growth: 0,
+            ..Default::default()
+        });
+        eat(&game);
+        step(&game);
+        assert_eq!(game.snake().len(), 1);
+        assert_eq!(game.stats().score, 1);
+    }
+
+    #[test]
+    fn single_cell_can_reverse() {
+        let game = game(GameOptions::default());
+        game.rotate_to(MoveTo::Left);
+        assert_eq!(game.direction(), MoveTo::Left);
+    }
+
+    #[test]
+    fn long_snake_cannot_reverse() {
+        // no food is eaten yet
+        let game = game(GameOptions {
+            initial_length: 2,
+            ..Default::default()
+        });
+        game.rotate_to(MoveTo::Left);
+        assert_eq!(game.direction(), MoveTo::Right);
+        game.rotate_to(MoveTo::Up);
+        assert_eq!(game.direction(), MoveTo::Up);
+    }
+
+    #[test]
+    fn snake_cannot_reverse_after_eating() {
+        let game = game(GameOptions::default());
+        eat(&game);
+        game.rotate_to(MoveTo::Left);
+        assert_eq!(game.direction(), MoveTo::Right);
+    }
+
+    #[test]
+    fn walls_kill() {
+        let game = game(GameOptions {
+            walls_kill: true,
+            ..Default::default()
+        });
+        for _ in 0..4 {
+            step(&game);
+        }
+        assert_eq!(game.head(), Pos::new(9, 3));
+        step(&game);
+        let stats = game.stats();
+        assert_eq!(stats.status, GameStatus::Fail);
+        assert_eq!(stats.death, Some(DeathCause::Wall));
+        assert_eq!(game.head(), Pos::new(9, 3));
+    }
+
+    #[test]
+    fn snake_goes_through_walls() {
+        let game = game(GameOptions::default());
+        for _ in 0..5 {
+            step(&game);
+        }
+        assert_eq!(game.head(), Pos::new(0, 3));
+        assert_eq!(game.stats().status, GameStatus::Play);
+    }
 }
@@ -1,5 +1,7 @@
+mod error;
 mod game;
 mod types;
 
-pub use game::{Game, GameEvent, GameStatus, Stats};
+pub use error::GameError;
+pub use game::{DeathCause, Game, GameEvent, GameOptions, GameStatus, Stats};
 pub use types::{CoordType, MoveTo, Pos};
@@ -70,10 +70,52 @@ impl Pos {
     pub fn wrapping_add(self, rhs: Self, rect: Self) -> Self {
         Self::new_coord((self.x + rhs.x) % rect.x, (self.y + rhs.y) % rect.y)
     }
+    /// Neighbour cell in direction, with wrapping inside some rectangle
+    pub(crate) fn moved(self, to: MoveTo, rect: Self) -> Self {
+        let (x, y) = (rect.x.0, rect.y.0);
+        let shift = match to {
+            MoveTo::Left => (x - 1, 0),
+            MoveTo::Right => (x + 1, 0),
+            MoveTo::Up => (0, y - 1),
+            MoveTo::Down => (0, y + 1),
+        };
+        self.wrapping_add(shift.into(), rect)
+    }
+    /// Direction of neighbour cell inside field of size `rect`, taking wrapping
+    /// into account. Returns `None` if cells are not neighbours
+    pub fn direction_to(self, to: Self, rect: Self) -> Option<MoveTo> {
+        let (x, y, w, h) = (self.x.0, self.y.0, rect.x.0, rect.y.0);
+        let next = |v: CoordType, size: CoordType| (v + 1) % size;
+        let prev = |v: CoordType, size: CoordType| (v + size - 1) % size;
+        if y == to.y.0 {
+            if to.x.0 == prev(x, w) {
+                return Some(MoveTo::Left);
+            }
+            if to.x.0 == next(x, w) {
+                return Some(MoveTo::Right);
+            }
+        }
+        if x == to.x.0 {
+            if to.y.0 == prev(y, h) {
+                return Some(MoveTo::Up);
+            }
+            if to.y.0 == next(y, h) {
+                return Some(MoveTo::Down);
+            }
+        }
+        None
+    }
     /// `x * y`
     pub(crate) fn area(&self) -> CoordType {
         self.x.0 * self.y.0
     }
+    /// Check that position can be used as field size: not empty, and moving
+    /// inside it can't overflow
+    pub(crate) fn is_valid_size(&self) -> bool {
+        let (x, y) = (self.x.0, self.y.0);
+        const MAX: CoordType = CoordType::MAX / 2;
+        (1..=MAX).contains(&x) && (1..=MAX).contains(&y) && x.checked_mul(y).is_some()
+    }
 }
 
 impl From<(CoordType, CoordType)> for Pos {
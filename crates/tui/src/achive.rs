@@ -1,19 +1,35 @@
-use std::{collections::HashMap, path::PathBuf, str::FromStr};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::SystemTime,
+};
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
 
-use crate::difficulty::DifficultyKind;
+use crate::{
+    config::validate_player,
+    csv,
+    difficulty::{DifficultyKind, GameMode},
+    paths::config_dif,
+    storage,
+};
 
 const FILE: &str = "achivements.csv";
-const SEP: &str = ",";
+/// Version of file format. Version 1 didn't have mode, version 2 didn't have
+/// date
+const VERSION: u32 = 3;
 
-pub type AchivementMap = HashMap<String, Vec<Achivement>>;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Achivement {
     pub username: String,
     pub difficulty: DifficultyKind,
+    pub mode: GameMode,
     pub score: usize,
+    /// When score was set. Unknown for scores saved by old versions
+    #[serde(default)]
+    pub date: Option<DateTime<Local>>,
 }
 
 impl PartialOrd for Achivement {
@@ -24,112 +40,367 @@ impl PartialOrd for Achivement {
 
 impl Ord for Achivement {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        (&self.username, self.difficulty).cmp(&(&other.username, other.difficulty))
+        (&self.username, self.difficulty, self.mode).cmp(&(
+            &other.username,
+            other.difficulty,
+            other.mode,
+        ))
     }
 }
 
-/// Returns Ok(Some(_)) if file was updated
-pub fn save_achivement(
-    achivements: &[Achivement],
-    achivement: Achivement,
-) -> Result<Option<Vec<Achivement>>> {
-    // get index instead of element to not get &mut [_] or do not clone if possible
-    let ind = achivements
-        .iter()
-        .position(|e| e.username == achivement.username && e.difficulty == achivement.difficulty);
-    let mut achivements = match ind {
-        Some(i) if achivements[i].score >= achivement.score => return Ok(None),
-        _ => achivements.to_vec(),
-    };
-    match ind {
-        Some(i) => achivements[i] = achivement,
-        None => achivements.push(achivement),
+/// Format of exported achivements
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Guess format by file extension, CSV is used for unknown extensions
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
     }
+}
+
+impl FromStr for ExportFormat {
+    type Err = &'static str;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err("unknown format, expected csv or json"),
+        }
+    }
+}
+
+/// Merge achivement with saved ones. File is re-read, so scores saved by
+/// other instances are kept. Returns Ok(Some(_)) if file was updated
+pub fn save_achivement(achivement: Achivement) -> Result<Option<Vec<Achivement>>> {
+    let _lock = storage::lock(&achivements_file())?;
+    let (achivements, changed) = merge_achivements(read_file()?, vec![achivement]);
+    if changed == 0 {
+        return Ok(None);
+    }
+    write_achivements(&achivements)?;
+
+    Ok(Some(achivements))
+}
+
+/// Keep best score for every user, difficulty and mode. Returns merged
+/// achivements and number of added or improved scores
+pub fn merge_achivements(
+    mut achivements: Vec<Achivement>,
+    other: Vec<Achivement>,
+) -> (Vec<Achivement>, usize) {
+    let mut changed = 0;
+    for achivement in other {
+        // same user, difficulty and mode
+        let ind = achivements.iter().position(|e| e.cmp(&achivement).is_eq());
+        match ind {
+            Some(i) if achivements[i].score >= achivement.score => continue,
+            Some(i) => achivements[i] = achivement,
+            None => achivements.push(achivement),
+        }
+        changed += 1;
+    }
     achivements.sort_unstable();
-    let res = achivements
+    (achivements, changed)
+}
+
+/// Serialize achivements for moving to another machine. CSV starts with
+/// version line, so it can be imported by later versions
+pub fn export_achivements(achivements: &[Achivement], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Csv => {
+            Ok(storage::version_line(VERSION) + "\n" + &achivements2csv(achivements))
+        }
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(achivements).context("failed to serialize achivements")
+        }
+    }
+}
+
+/// Merge achivements exported on another machine into saved ones. Returns
+/// number of added or improved scores
+pub fn import_achivements(path: &Path, format: ExportFormat) -> Result<usize> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let imported = parse_export(&text, &path.display().to_string(), format)?;
+    let _lock = storage::lock(&achivements_file())?;
+    let (achivements, changed) = merge_achivements(read_file()?, imported);
+    if changed > 0 {
+        write_achivements(&achivements)?;
+    }
+    Ok(changed)
+}
+
+/// Parse achivements serialized by [`export_achivements`]. `source` is used
+/// in errors
+fn parse_export(text: &str, source: &str, format: ExportFormat) -> Result<Vec<Achivement>> {
+    let imported: Vec<Achivement> = match format {
+        ExportFormat::Csv => storage::parse(text, source, VERSION, parse_achivement)?.1,
+        ExportFormat::Json => {
+            serde_json::from_str(text).with_context(|| format!("invalid file {source}"))?
+        }
+    };
+    for a in &imported {
+        validate_player(&a.username).with_context(|| format!("invalid file {source}"))?;
+    }
+    Ok(imported)
+}
+
+/// Serialize achivements to the same CSV, which is used for storing them
+pub fn achivements2csv(achivements: &[Achivement]) -> String {
+    let res: String = achivements
         .iter()
         .map(|a| {
-            if a.username.contains(SEP) {
-                return Err(anyhow!("username cannot contain {SEP}"));
-            }
-            Ok([
-                a.username.clone(),
+            [
+                csv::field(&a.username),
                 a.difficulty.to_string().to_lowercase(),
+                a.mode.to_string(),
                 a.score.to_string(),
+                a.date
+                    .map(|d| d.to_rfc3339_opts(SecondsFormat::Secs, false))
+                    .unwrap_or_default(),
             ]
-            .join(SEP)
-                + "\n")
+            .join(csv::SEP)
+                + "\n"
         })
-        .collect::<Result<String>>()?;
+        .collect();
 
-    let res = achivements_header() + "\n" + res.as_str();
-
-    std::thread::spawn(|| {
-        if let Err(e) = std::fs::write(achivements_file(), res) {
-            let _ = std::fs::write(
-                error_log_file(),
-                format!("failed to write achivements: {e}"),
-            );
-        }
-    });
+    achivements_header() + "\n" + res.as_str()
+}
 
-    Ok(Some(achivements))
+/// Remove all saved achivements
+pub fn reset_achivements() -> Result<()> {
+    let _lock = storage::lock(&achivements_file())?;
+    write_achivements(&[])
 }
 
 pub fn read_achivements() -> Result<Vec<Achivement>> {
-    let file = achivements_file();
-    if !file.exists() {
-        std::fs::create_dir_all(config_dif()).context("failed to create config directory")?;
-        std::fs::write(&file, achivements_header() + "\n")
-            .context("failed to write achivements")?;
-    }
-
-    std::fs::read_to_string(file)
-        .context("failed to read achivements")?
-        .lines()
-        .skip(1)
-        .map(|l| l.split(SEP).map(|v| v.trim()).collect())
-        .map(|l: Vec<_>| {
-            let &[username, difficulty, score] = l.as_slice() else {
-                return Err(anyhow!("unexpected elements count in entry"));
-            };
-
-            Ok(Achivement {
-                username: username.to_owned(),
-                difficulty: DifficultyKind::from_str(difficulty)
-                    .map_err(|e| anyhow!("{e}"))
-                    .context("invalid difficulty")?,
-                score: str::parse(score).context("invalid score")?,
-            })
-        })
-        .collect()
+    let _lock = storage::lock(&achivements_file())?;
+    read_file()
+}
+
+/// Time of last change of saved achivements
+pub fn achivements_modified() -> Option<SystemTime> {
+    storage::modified(&achivements_file())
 }
 
-pub fn achivements2map(achivements: &[Achivement]) -> AchivementMap {
-    let mut res = HashMap::new();
-    for a in achivements {
-        res.entry(a.username.clone())
-            .and_modify(|e: &mut Vec<_>| e.push(a.clone()))
-            .or_insert(vec![a.clone()]);
+/// Read achivements, converting file from older version if needed. Should be
+/// called with lock
+fn read_file() -> Result<Vec<Achivement>> {
+    let (version, achivements) = storage::read(&achivements_file(), VERSION, parse_achivement)?;
+    if version != Some(VERSION) {
+        write_achivements(&achivements)?;
     }
-    res
+    Ok(achivements)
+}
+
+fn parse_achivement(version: u32, fields: &[String]) -> Result<Achivement> {
+    let fields: Vec<_> = fields.iter().map(|f| f.trim()).collect();
+    let (username, difficulty, mode, score, date) = match (version, fields.as_slice()) {
+        (1, &[username, difficulty, score]) => (username, difficulty, "classic", score, ""),
+        (_, &[username, difficulty, mode, score]) => (username, difficulty, mode, score, ""),
+        (_, &[username, difficulty, mode, score, date]) => {
+            (username, difficulty, mode, score, date)
+        }
+        _ => return Err(anyhow!("unexpected elements count in entry")),
+    };
+
+    Ok(Achivement {
+        username: username.to_owned(),
+        difficulty: DifficultyKind::from_str(difficulty)
+            .map_err(|e| anyhow!("{e}"))
+            .context("invalid difficulty")?,
+        mode: GameMode::from_str(mode)
+            .map_err(|e| anyhow!("{e}"))
+            .context("invalid mode")?,
+        score: str::parse(score).context("invalid score")?,
+        date: match date {
+            "" => None,
+            d => Some(
+                DateTime::parse_from_rfc3339(d)
+                    .context("invalid date")?
+                    .with_timezone(&Local),
+            ),
+        },
+    })
+}
+
+fn write_achivements(achivements: &[Achivement]) -> Result<()> {
+    storage::write(&achivements_file(), VERSION, &achivements2csv(achivements))
 }
 
 fn achivements_header() -> String {
-    ["username", "difficulty", "score"].join(SEP)
+    ["username", "difficulty", "mode", "score", "date"].join(csv::SEP)
 }
 
 fn achivements_file() -> PathBuf {
     config_dif().join(FILE)
 }
 
-fn error_log_file() -> PathBuf {
-    config_dif().join("error.log")
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::difficulty::CustomRules;
+
+    fn achivement(username: &str, difficulty: DifficultyKind, score: usize) -> Achivement {
+        Achivement {
+            username: username.to_owned(),
+            difficulty,
+            mode: GameMode::Classic,
+            score,
+            date: None,
+        }
+    }
+
+    #[test]
+    fn merge_keeps_best_score() {
+        let saved = vec![achivement("bob", DifficultyKind::Easy, 10)];
+        let (merged, changed) = merge_achivements(
+            saved.clone(),
+            vec![achivement("bob", DifficultyKind::Easy, 5)],
+        );
+        assert_eq!(changed, 0);
+        assert_eq!(merged, saved);
+
+        let (merged, changed) =
+            merge_achivements(saved, vec![achivement("bob", DifficultyKind::Easy, 15)]);
+        assert_eq!(changed, 1);
+        assert_eq!(merged, [achivement("bob", DifficultyKind::Easy, 15)]);
+    }
+
+    #[test]
+    fn merge_equal_score_is_not_change() {
+        let saved = vec![achivement("bob", DifficultyKind::Easy, 10)];
+        let (_, changed) = merge_achivements(saved.clone(), saved);
+        assert_eq!(changed, 0);
+    }
+
+    #[test]
+    fn merge_separates_user_difficulty_and_mode() {
+        let saved = vec![achivement("bob", DifficultyKind::Easy, 10)];
+        let custom = Achivement {
+            mode: GameMode::Custom(CustomRules::default()),
+            ..achivement("bob", DifficultyKind::Custom, 1)
+        };
+        let other = vec![
+            achivement("alice", DifficultyKind::Easy, 1),
+            achivement("bob", DifficultyKind::Hard, 1),
+            Achivement {
+                mode: GameMode::Custom(CustomRules::default()),
+                ..achivement("bob", DifficultyKind::Easy, 1)
+            },
+            custom.clone(),
+        ];
+        let (merged, changed) = merge_achivements(saved, other);
+        assert_eq!(changed, 4);
+        assert_eq!(merged.len(), 5);
+        assert!(merged.contains(&custom));
+        // sorted by user
+        assert_eq!(merged[0].username, "alice");
+    }
+
+    #[test]
+    fn merge_counts_every_changed_row() {
+        let other = vec![
+            achivement("bob", DifficultyKind::Easy, 1),
+            achivement("bob", DifficultyKind::Easy, 2),
+            achivement("bob", DifficultyKind::Easy, 1),
+        ];
+        let (merged, changed) = merge_achivements(vec![], other);
+        assert_eq!(changed, 2);
+        assert_eq!(merged, [achivement("bob", DifficultyKind::Easy, 2)]);
+    }
+
+    fn exported() -> Vec<Achivement> {
+        let date = DateTime::parse_from_rfc3339("2024-05-01T12:30:00+02:00").unwrap();
+        vec![
+            achivement("bob", DifficultyKind::Easy, 10),
+            Achivement {
+                username: "Smith, \"Agent\"".to_owned(),
+                mode: GameMode::Custom(CustomRules::default()),
+                date: Some(date.with_timezone(&Local)),
+                ..achivement("", DifficultyKind::Custom, 3)
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let text = export_achivements(&exported(), ExportFormat::Csv).unwrap();
+        assert!(text.starts_with(&storage::version_line(VERSION)));
+        let parsed = parse_export(&text, "test", ExportFormat::Csv).unwrap();
+        assert_eq!(parsed, exported());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let text = export_achivements(&exported(), ExportFormat::Json).unwrap();
+        let parsed = parse_export(&text, "test", ExportFormat::Json).unwrap();
+        assert_eq!(parsed, exported());
+    }
+
+    fn parse(version: u32, row: &str) -> Result<Achivement> {
+        parse_achivement(version, &csv::split(row).unwrap())
+    }
+
+    #[test]
+    fn parse_version_1_row() {
+        let a = parse(1, "bob, easy, 5").unwrap();
+        assert_eq!(a, achivement("bob", DifficultyKind::Easy, 5));
+    }
+
+    #[test]
+    fn parse_version_2_row() {
+        let a = parse(2, "bob,custom,10mps-1len-1grow-wrap-off,5").unwrap();
+        let expected = Achivement {
+            mode: GameMode::Custom(CustomRules::default()),
+            ..achivement("bob", DifficultyKind::Custom, 5)
+        };
+        assert_eq!(a, expected);
+    }
+
+    #[test]
+    fn parse_version_3_row() {
+        let a = parse(3, "bob,easy,classic,5,").unwrap();
+        assert_eq!(a, achivement("bob", DifficultyKind::Easy, 5));
+
+        let a = parse(3, "bob,easy,classic,5,2024-05-01T12:30:00+02:00").unwrap();
+        let date = DateTime::parse_from_rfc3339("2024-05-01T12:30:00+02:00").unwrap();
+        assert_eq!(a.date, Some(date.with_timezone(&Local)));
+    }
+
+    #[test]
+    fn parse_invalid_row() {
+        assert!(parse(3, "bob,easy,5").is_err());
+        assert!(parse(3, "bob,easy,classic,5,,").is_err());
+        assert!(parse(3, "bob,unknown,classic,5,").is_err());
+        assert!(parse(3, "bob,easy,classic,-5,").is_err());
+        assert!(parse(3, "bob,easy,classic,5,yesterday").is_err());
+    }
 
-fn config_dif() -> PathBuf {
-    dirs::config_dir()
-        .expect("config dir always exists")
-        .join(crate::PKG_NAME)
+    #[test]
+    fn invalid_rows_are_skipped() {
+        let text = "username,difficulty,score\nbob,easy,5\nskipped-row\nalice,hard,1\n";
+        let (version, parsed, skipped) =
+            storage::parse_rows(text, "test", VERSION, parse_achivement).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(
+            parsed,
+            [
+                achivement("bob", DifficultyKind::Easy, 5),
+                achivement("alice", DifficultyKind::Hard, 1),
+            ]
+        );
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("test:3: skipped \"skipped-row\""));
+    }
 }
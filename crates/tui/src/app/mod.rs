@@ -1,12 +1,22 @@
-use std::time::{Duration, Instant};
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant, SystemTime},
+};
 
 use anyhow::Result;
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::{Alignment, Rect},
+    crossterm::{
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+            KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+        },
+        ExecutableCommand,
+    },
+    layout::{self, Alignment, Margin, Rect},
     style::Stylize,
-    symbols::{border, Marker},
+    symbols::border,
+    text::Line,
     widgets::{
         block::{Position, Title},
         Block, Widget,
@@ -15,12 +25,26 @@ use ratatui::{
 
 use lib::{CoordType, Game, GameEvent, GameStatus, MoveTo, Pos};
 
+use screens::{
+    Click, GameScreen, HelpScreen, Keybind, LeaderboardScreen, MenuScreen, PauseScreen, Screen,
+    Transition,
+};
+
 use crate::{
-    achive::{achivements2map, read_achivements, save_achivement, Achivement, AchivementMap},
+    achive::{achivements_modified, read_achivements, save_achivement, Achivement},
+    badges::{add_unlocks, read_unlocks, Badge, BadgeTracker, Unlock},
+    config::{system_player, validate_player, Config, Renderer},
     difficulty::*,
+    history::{self, Run},
+    keys::{Action, KeyBindings, KeyContext},
+    sound::{SoundEvent, Sounds},
+    strings,
+    theme::{ColorMode, Theme},
+    widgets::{FinishState, GameSummary, NewBest},
 };
 
 mod render;
+mod screens;
 
 const FPS_CONVERT: u64 = 1000 * 1000;
 // interesting, dur2fps(fps(60)) == 62
@@ -30,59 +54,137 @@ pub const fn fps(fps: u64) -> Duration {
 pub const fn dur2fps(dur: Duration) -> u64 {
     FPS_CONVERT / dur.as_micros() as u64
 }
-const FPS20: Duration = fps(20);
 const FPS60: Duration = fps(60);
 
-const DEFAULT_UI_FPS: Duration = FPS20;
 const DEFAULT_EVENT_FPS: Duration = FPS60;
 
-const DRAW_MARKER: Marker = Marker::Block;
+/// How often saved achivements are checked for changes from other instances
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Delay before snake starts moving after pause
+const RESUME_COUNTDOWN: Duration = Duration::from_secs(3);
 
-/// Scale frame size to number of cells
-const SCALE_SIZE: (f64, f64) = (4.1, 2.2);
+/// How long unlocked badge is shown
+const TOAST_TIME: Duration = Duration::from_secs(3);
+
+/// Smallest game field which is still playable
+const MIN_GAME_SIZE: Pos = Pos::new(10, 6);
 
 #[derive(Debug, Default)]
 pub struct App {
     game: Game,
     block_size: Pos,
     game_size: Pos,
-    state: AppState,
+    screens: Vec<Box<dyn Screen>>,
+    /// Terminal can't fit [`MIN_GAME_SIZE`]
+    too_small: bool,
+    exited: bool,
     difficulty: Difficulty,
+    /// Time snake moved in current game. Snake speed can change during game
+    played: Duration,
 
     ui_fps: Duration,
     event_fps: Duration,
     paused: bool,
+    /// When snake starts moving after pause
+    resume_at: Option<Instant>,
 
     user: String,
+    /// Field size set by user
+    fixed_size: Option<Pos>,
+    seed: Option<u64>,
+    no_save: bool,
     show_achivements_grouped: bool,
     achivements: Vec<Achivement>,
-    achivements_map: AchivementMap,
+    /// Time of last change of saved achivements, when they were read
+    achivements_modified: Option<SystemTime>,
+    /// Scores of user and of everyone on current difficulty, before current
+    /// game started
+    best_scores: (Option<usize>, Option<usize>),
+    /// Score of current game is saved, it is saved once when game ends
+    score_saved: bool,
+    /// Difficulty and mode of current game. Difficulty can be changed in menu
+    /// before abandoned game is saved
+    game_difficulty: (DifficultyKind, GameMode),
+    /// Badges of all users
+    badges: Vec<Unlock>,
+    badge_tracker: BadgeTracker,
+    /// Recently unlocked badges
+    toasts: Vec<(Badge, Instant)>,
+    /// Areas of current frame, which react to mouse clicks
+    clickable: RefCell<Vec<(Rect, Click)>>,
+    sounds: Sounds,
 
     debug: bool,
     debug_info: Debug,
     error: Option<Result<()>>,
+
+    config: Config,
+    keys: KeyBindings,
+    theme: Theme,
+    color_mode: ColorMode,
 }
 
-#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
-enum AppState {
-    #[default]
-    Play,
-    SelectDifficulty {
-        was_paused: bool,
-    },
-    Exit,
+/// Startup settings, usually from command line
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Overrides user from config
+    pub user: Option<String>,
+    pub difficulty: Option<DifficultyKind>,
+    pub size: Option<Pos>,
+    pub seed: Option<u64>,
+    pub no_save: bool,
+    /// Start game without showing main menu
+    pub play: bool,
 }
 
 impl App {
-    pub fn run(&mut self, term: &mut crate::tui::Tui, user: String) -> Result<()> {
-        self.user = user;
+    pub fn new(options: Options, mut config: Config) -> Result<Self> {
+        // system login can be anything, so only names set by player are checked
+        let user = match options.user.or_else(|| config.user.clone()) {
+            Some(user) => {
+                validate_player(&user)?;
+                user
+            }
+            None => system_player(),
+        };
+        config.add_player(&user);
+
+        let mut app = Self {
+            keys: KeyBindings::new(&config.keys)?,
+            theme: config.theme()?,
+            color_mode: config.ui.mode.with_env(),
+            user,
+            fixed_size: options.size,
+            seed: options.seed,
+            no_save: options.no_save,
+            screens: vec![Box::new(MenuScreen::default())],
+            show_achivements_grouped: config.show_achivements_grouped,
+            debug: config.debug,
+            ..Default::default()
+        };
+        let difficulty = options.difficulty.unwrap_or(config.difficulty);
+        app.select_difficulty(difficulty);
+        app.difficulty.prev = difficulty;
+        app.difficulty.custom = config.custom_rules();
+        app.difficulty.update_fps(0);
+        app.config = config;
+        if options.play {
+            app.screens.push(Box::new(GameScreen));
+        }
+        Ok(app)
+    }
+    pub fn run(&mut self, term: &mut crate::tui::Tui) -> Result<()> {
         self.read_achivement();
+        self.read_badges();
         self.update_fps();
+        set_mouse_capture(self.config.ui.mouse)?;
 
         let mut global_tick = Instant::now();
-        let mut snake_tick = Instant::now();
+        let mut screen_tick = Instant::now();
+        let mut watch_tick = Instant::now();
 
-        while !self.exited() {
+        while !self.exited {
             self.handle_error()?;
 
             if global_tick.elapsed() < self.ui_fps {
@@ -101,24 +203,58 @@ impl App {
             })?;
 
             if let Some(e) = self.game.last_event() {
-                if e == GameEvent::FoodEat {
-                    self.update_achivement();
-                    self.handle_error()?;
-                    self.game.forgot_event(e);
+                match e {
+                    GameEvent::FoodEat => {
+                        let score = self.game.stats().score;
+                        self.difficulty.update_fps(score);
+                        self.update_fps();
+                        // only when previous best is beaten, not on every food.
+                        // First game of difficulty beats a best of 0
+                        if score == self.best_scores.0.unwrap_or(0) + 1 {
+                            self.play_sound(SoundEvent::PersonalBest);
+                        } else {
+                            self.play_sound(SoundEvent::Food);
+                        }
+                    }
+                    // events are reported once, so every game is recorded once
+                    GameEvent::Fail => {
+                        self.update_achivement();
+                        self.record_run();
+                        self.handle_error()?;
+                        self.play_sound(SoundEvent::Death);
+                    }
+                    GameEvent::Win => {
+                        self.update_achivement();
+                        self.record_run();
+                        self.handle_error()?;
+                        self.play_sound(SoundEvent::Win);
+                    }
+                    GameEvent::GameStart => {}
                 }
+                self.game.forgot_event(e);
             }
 
-            if snake_tick.elapsed() > self.difficulty.fps.duration() {
+            if watch_tick.elapsed() > WATCH_INTERVAL {
+                self.watch_achivements();
+                watch_tick = Instant::now();
+            }
+
+            let tick = self.with_screen(|s, app| s.tick_duration(app));
+            if tick.is_none_or(|t| screen_tick.elapsed() > t) {
                 self.handle_events()?;
 
-                if !self.paused {
-                    self.move_snake();
+                if !self.too_small {
+                    if let Some(t) = self.with_screen(|s, app| s.tick(app)) {
+                        self.apply_transition(t);
+                    }
                 }
-                snake_tick = Instant::now();
+                screen_tick = Instant::now();
             }
         }
+        // game could be quit before it ended
+        self.update_achivement();
 
-        Ok(())
+        self.handle_error()
     }
 
     // -------- handle events --------
@@ -127,78 +263,274 @@ impl App {
         if event::poll(self.event_fps)? {
             match event::read()? {
                 Event::Key(e) if e.kind == KeyEventKind::Press => self.handle_key_event(e),
+                Event::Mouse(e) => self.handle_mouse_event(e),
+                Event::FocusLost => {
+                    if let Some(t) = self.with_screen(|s, app| s.on_focus_lost(app)) {
+                        self.apply_transition(t);
+                    }
+                }
                 _ => {}
             }
         }
         Ok(())
     }
-    #[allow(clippy::single_match)]
     fn handle_key_event(&mut self, event: KeyEvent) {
-        // common keys
-        match event.code {
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Char('r') => self.restart(),
-            KeyCode::F(3) => self.toggle_debug(),
-            _ => {}
+        // works everywhere, also in text fields
+        if event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL) {
+            self.exit();
+            return;
         }
-
-        // keys for selecting difficulty
-        if self.selecting_difficulty() {
-            match event.code {
-                KeyCode::Char('s') => self.select_difficulty(DifficultyKind::Secret),
-                KeyCode::Left => self.select_difficulty(self.difficulty.kind.prev()),
-                KeyCode::Right => self.select_difficulty(self.difficulty.kind.next()),
-                KeyCode::Char('d') => self.undo_difficulty(),
-                KeyCode::Enter => self.submit_difficulty(),
-                _ => {}
-            }
+        if let Some(Some(t)) = self.with_screen(|s, app| s.handle_key(app, event)) {
+            self.apply_transition(t);
             return;
         }
 
-        // keys when playing
-        match event.code {
-            KeyCode::Char('d') => self.set_select_difficulty(),
-            KeyCode::Char('a') => self.toggle_achivements_grouped(),
+        let Some(context) = self.with_screen(|s, _| s.context()) else {
+            return;
+        };
+        let Some(action) = self.keys.action(context, event.code) else {
+            return;
+        };
+
+        // common keys
+        match action {
+            Action::Quit => self.exit(),
+            Action::Debug => self.toggle_debug(),
             _ => {}
         }
 
-        if self.game_ended() {
+        if self.too_small {
             return;
         }
 
-        // keys when playing except fail/win
-        match event.code {
-            KeyCode::Esc => self.toggle_pause(),
-            _ => {}
+        if action == Action::Help && context != KeyContext::Help {
+            let help = self.help_screen(self.keybinds());
+            self.apply_transition(Transition::push(help));
+            return;
         }
 
-        if self.paused {
-            return;
+        if let Some(t) = self.with_screen(|s, app| s.handle_action(app, action)) {
+            self.apply_transition(t);
         }
+    }
 
-        // keys for snake rotate
-        match event.code {
-            KeyCode::Left => self.rotate_snake(MoveTo::Left),
-            KeyCode::Right => self.rotate_snake(MoveTo::Right),
-            KeyCode::Up => self.rotate_snake(MoveTo::Up),
-            KeyCode::Down => self.rotate_snake(MoveTo::Down),
-            _ => {}
+    fn handle_mouse_event(&mut self, event: MouseEvent) {
+        let Some(context) = self.with_screen(|s, _| s.context()) else {
+            return;
+        };
+        let takes_text = self.with_screen(|s, _| s.takes_text()) == Some(true);
+        let action = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let pos = layout::Position::new(event.column, event.row);
+                // last registered area is drawn on top
+                let click = self
+                    .clickable
+                    .borrow()
+                    .iter()
+                    .rev()
+                    .find(|(area, _)| area.contains(pos))
+                    .map(|&(_, click)| click);
+                match click {
+                    Some(Click::Action(action)) => action,
+                    Some(Click::Item(i)) => {
+                        if let Some(t) = self.with_screen(|s, app| s.handle_click(app, i)) {
+                            self.apply_transition(t);
+                        }
+                        return;
+                    }
+                    Some(Click::Achivement(i)) => {
+                        if let Some(a) = self.achivements.get(i) {
+                            let screen = LeaderboardScreen::showing(self, a);
+                            // game continues with countdown after leaderboard
+                            if context == KeyContext::Play && !self.game_ended() {
+                                self.apply_transition(Transition::push(PauseScreen::default()));
+                            }
+                            self.apply_transition(Transition::push(screen));
+                        }
+                        return;
+                    }
+                    None => return,
+                }
+            }
+            // wheel would turn the snake in game, or type into text field
+            MouseEventKind::ScrollUp if context != KeyContext::Play && !takes_text => {
+                Action::MoveUp
+            }
+            MouseEventKind::ScrollDown if context != KeyContext::Play && !takes_text => {
+                Action::MoveDown
+            }
+            _ => return,
+        };
+        // same as pressing the key, so screens don't need to handle clicks
+        if let Some(code) = self.keys.code(action) {
+            self.handle_key_event(KeyEvent::from(code));
         }
     }
+    /// Make area react to mouse clicks until next frame
+    fn clickable(&self, area: Rect, click: Click) {
+        self.clickable.borrow_mut().push((area, click));
+    }
 
-    // -------- get values --------
+    // -------- screens --------
 
-    fn exited(&self) -> bool {
-        self.state == AppState::Exit
+    /// Call function on top screen
+    fn with_screen<T>(&mut self, f: impl FnOnce(&mut dyn Screen, &mut Self) -> T) -> Option<T> {
+        // take screen out, so it can change the app
+        let mut screen = self.screens.pop()?;
+        let res = f(screen.as_mut(), self);
+        self.screens.push(screen);
+        Some(res)
     }
-    fn playing(&self) -> bool {
-        self.state == AppState::Play
+    fn apply_transition(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(mut screen) => {
+                screen.on_enter(self);
+                self.screens.push(screen);
+            }
+            Transition::Pop => self.pop_screen(),
+            Transition::Replace(screen) => {
+                self.pop_screen();
+                self.apply_transition(Transition::Push(screen));
+            }
+            Transition::Menu => {
+                while self.screens.len() > 1 {
+                    self.pop_screen();
+                }
+            }
+            Transition::Quit => self.exit(),
+        }
+    }
+    fn pop_screen(&mut self) {
+        if let Some(mut screen) = self.screens.pop() {
+            screen.on_exit(self);
+        }
+        if self.screens.is_empty() {
+            self.exit();
+        }
+    }
+    /// Keybindings of top screen
+    fn keybinds(&self) -> Vec<Keybind> {
+        self.screens
+            .last()
+            .map(|s| s.keybinds(self))
+            .unwrap_or_default()
+    }
+    /// Keys which work on every screen, except help
+    fn global_keybinds(&self, debug: bool) -> Vec<Keybind> {
+        use crate::strings::tr::keybind as tr;
+
+        let mut res = vec![];
+        if debug {
+            res.push(Keybind::new(tr::debug(), [Action::Debug]));
+        }
+        res.push(Keybind::new(tr::quit(), [Action::Quit]));
+        res
     }
+    /// Help with keys of some screen
+    fn help_screen(&self, mut keybinds: Vec<Keybind>) -> HelpScreen {
+        use crate::strings::tr::keybind as tr;
+
+        keybinds.push(Keybind::new(tr::help(), [Action::Help]));
+        keybinds.extend(self.global_keybinds(true));
+        HelpScreen::new(keybinds)
+    }
+
+    // -------- get values --------
+
     fn game_ended(&self) -> bool {
         self.game.stats().status != GameStatus::Play
     }
-    fn selecting_difficulty(&self) -> bool {
-        matches!(self.state, AppState::SelectDifficulty { .. })
+    /// Game is opened and not finished, e.g. when settings are opened from
+    /// pause menu
+    fn game_in_progress(&self) -> bool {
+        !self.game_ended() && self.screens.iter().any(|s| s.context() == KeyContext::Play)
+    }
+    /// Seconds left before snake starts moving after pause
+    fn countdown(&self) -> Option<u64> {
+        let left = self.resume_at?.checked_duration_since(Instant::now())?;
+        Some(left.as_millis().div_ceil(1000) as u64).filter(|&s| s > 0)
+    }
+    /// Renderer which can be used with current color mode. Only symbols can be
+    /// used without colors
+    fn renderer(&self) -> Renderer {
+        match self.config.ui.renderer {
+            Renderer::Canvas | Renderer::HalfBlock if !self.color_mode.colored() => Renderer::Cells,
+            r => r,
+        }
+    }
+    /// Terminal size required to fit [`MIN_GAME_SIZE`] or field size set by user
+    fn required_size(&self) -> Pos {
+        let (x, y) = self.fixed_size.unwrap_or(MIN_GAME_SIZE).into();
+        let [scale_x, scale_y] = self.config.ui.scale;
+        match self.renderer() {
+            // field takes half of terminal, plus borders
+            Renderer::HalfBlock => Pos::new(
+                (x as CoordType + 2) * 2 + 1,
+                (y.div_ceil(2) as CoordType + 2) * 2 + 1,
+            ),
+            Renderer::Canvas | Renderer::Cells => Pos::new(
+                (x as f64 * scale_x).ceil() as CoordType,
+                (y as f64 * scale_y).ceil() as CoordType,
+            ),
+        }
+    }
+    /// Maximum field size for current terminal size
+    fn field_capacity(&self) -> Pos {
+        let (x, y) = self.block_size.into();
+        match self.renderer() {
+            Renderer::HalfBlock => {
+                let area = render::field_inner(Rect::new(0, 0, x as u16, y as u16));
+                Pos::new(area.width as CoordType, area.height as CoordType * 2)
+            }
+            Renderer::Canvas | Renderer::Cells => {
+                let [scale_x, scale_y] = self.config.ui.scale;
+                Pos::new(
+                    (x as f64 / scale_x) as CoordType,
+                    (y as f64 / scale_y) as CoordType,
+                )
+            }
+        }
+    }
+    fn terminal_fits(&self) -> bool {
+        let (x, y) = self.field_capacity().into();
+        let (min_x, min_y) = self.fixed_size.unwrap_or(MIN_GAME_SIZE).into();
+        x >= min_x && y >= min_y
+    }
+    /// Results of current game
+    fn game_summary(&self) -> GameSummary {
+        let stats = self.game.stats();
+        let score = stats.score;
+        let mode = self.difficulty.mode();
+        let scores = self
+            .achivements
+            .iter()
+            .filter(|a| a.difficulty == self.difficulty.kind && a.mode == mode);
+        let rank = scores.filter(|a| a.score > score).count() + 1;
+
+        let (personal, global) = self.best_scores;
+        let best = if score == 0 {
+            None
+        } else if global.is_none_or(|s| score > s) {
+            Some(NewBest::Global)
+        } else if personal.is_none_or(|s| score > s) {
+            Some(NewBest::Personal)
+        } else {
+            None
+        };
+
+        GameSummary {
+            state: match stats.status {
+                GameStatus::Win => FinishState::Win,
+                GameStatus::Play | GameStatus::Fail => FinishState::Fail,
+            },
+            score,
+            length: self.game.snake().len(),
+            duration: self.played,
+            death: stats.death,
+            rank,
+            best,
+        }
     }
     fn difficulty_changed(&self) -> bool {
         self.difficulty.prev != self.difficulty.kind
@@ -215,27 +547,28 @@ impl App {
     fn reset_difficulty(&mut self) {
         self.difficulty.kind = self.difficulty.prev;
     }
-    fn undo_difficulty(&mut self) {
-        self.reset_difficulty();
-        if let AppState::SelectDifficulty { was_paused: false } = self.state {
-            self.unpause();
-        }
-        self.reset_app_state();
-    }
     fn submit_difficulty(&mut self) {
         if !self.difficulty_changed() {
-            self.unpause();
-            self.reset_app_state();
             return;
         }
         self.difficulty.prev = self.difficulty.kind;
-        self.difficulty.update_fps();
-        self.update_fps();
         self.restart();
+        self.save_config();
+    }
+    /// Play custom difficulty with rules of preset, and remember them
+    fn submit_custom(&mut self, preset: &str, rules: CustomRules) {
+        self.config.presets.insert(preset.to_string(), rules);
+        self.config.preset = Some(preset.to_string());
+        self.difficulty.custom = rules;
+        self.difficulty.kind = DifficultyKind::Custom;
+        self.difficulty.prev = DifficultyKind::Custom;
+        self.config.difficulty = DifficultyKind::Custom;
+        self.restart();
+        self.save_settings();
     }
     fn update_fps(&mut self) {
         let fps = self.difficulty.fps.duration();
-        self.ui_fps = std::cmp::min(DEFAULT_UI_FPS, fps);
+        self.ui_fps = std::cmp::min(self::fps(self.config.ui.fps), fps);
         self.event_fps = std::cmp::min(DEFAULT_EVENT_FPS, fps);
         self.debug_info.fps = format!(
             "{} / {} / {}",
@@ -244,84 +577,282 @@ impl App {
             dur2fps(self.event_fps),
         );
     }
+    /// Save score of current game, if it is the best one. Called when game
+    /// ends or is abandoned, as saving re-reads and rewrites the file
     fn update_achivement(&mut self) {
+        let score = self.game.stats().score;
+        if self.no_save || self.score_saved || score == 0 {
+            return;
+        }
+        self.score_saved = true;
+        let (difficulty, mode) = self.game_difficulty;
+        let best = self
+            .achivements
+            .iter()
+            .find(|a| a.username == self.user && a.difficulty == difficulty && a.mode == mode);
+        if best.is_some_and(|a| a.score >= score) {
+            return;
+        }
         let achivement = Achivement {
             username: self.user.clone(),
-            difficulty: self.difficulty.kind,
-            score: self.game.stats().score,
+            difficulty,
+            mode,
+            score,
+            date: Some(chrono::Local::now()),
         };
-        let res = save_achivement(&self.achivements, achivement);
+        let res = save_achivement(achivement);
         match res {
-            Ok(Some(a)) => self.save_achivements(a),
+            Ok(Some(a)) => {
+                self.achivements_modified = achivements_modified();
+                self.save_achivements(a);
+            }
             Ok(None) => {}
             e @ Err(_) => self.error = Some(e.map(|_| ())),
         }
     }
     fn read_achivement(&mut self) {
+        self.achivements_modified = achivements_modified();
         match read_achivements() {
             Ok(a) => self.save_achivements(a),
             e @ Err(_) => self.error = Some(e.map(|_| ())),
         }
     }
+    /// Add finished game to history
+    fn record_run(&mut self) {
+        if self.no_save {
+            return;
+        }
+        let stats = self.game.stats();
+        let (difficulty, mode) = self.game_difficulty;
+        let run = Run {
+            date: chrono::Local::now(),
+            username: self.user.clone(),
+            difficulty,
+            mode,
+            size: self.game.size(),
+            seed: self.game.seed(),
+            score: stats.score,
+            duration: self.played,
+            death: stats.death,
+        };
+        let res = history::append(&run).and_then(|_| history::prune(&self.config.history));
+        if let e @ Err(_) = res {
+            self.error = Some(e);
+        }
+    }
+    /// Read achivements again if they were changed by another instance
+    fn watch_achivements(&mut self) {
+        if achivements_modified() != self.achivements_modified {
+            self.read_achivement();
+        }
+    }
+    fn read_badges(&mut self) {
+        match read_unlocks() {
+            Ok(b) => self.badges = b,
+            Err(e) => self.error = Some(Err(e)),
+        }
+    }
+    /// Unlock badges with met conditions
+    fn update_badges(&mut self) {
+        if self.no_save {
+            return;
+        }
+        let met = self.badge_tracker.update(
+            &self.game,
+            self.difficulty.kind,
+            self.difficulty.fps.duration(),
+        );
+        let mut unlocked = vec![];
+        for badge in met {
+            if self.has_badge(badge) {
+                continue;
+            }
+            unlocked.push(Unlock {
+                username: self.user.clone(),
+                badge,
+                date: chrono::Local::now(),
+            });
+            self.toasts.push((badge, Instant::now()));
+        }
+        if !unlocked.is_empty() {
+            match add_unlocks(&unlocked) {
+                Ok(badges) => self.badges = badges,
+                Err(e) => self.error = Some(Err(e)),
+            }
+        }
+        self.toasts.retain(|(_, t)| t.elapsed() < TOAST_TIME);
+    }
+    fn has_badge(&self, badge: Badge) -> bool {
+        self.badges
+            .iter()
+            .any(|u| u.username == self.user && u.badge == badge)
+    }
     fn save_achivements(&mut self, achivements: Vec<Achivement>) {
         self.achivements = achivements;
-        self.achivements_map = achivements2map(&self.achivements);
+    }
+    /// Use new settings without saving them
+    fn apply_config(&mut self, config: Config) -> Result<()> {
+        config.validate()?;
+        self.keys = KeyBindings::new(&config.keys)?;
+        self.theme = config.theme()?;
+        self.color_mode = config.ui.mode.with_env();
+        if config.ui.language != self.config.ui.language {
+            strings::set_language(config.ui.language.as_deref())?;
+        }
+        if config.ui.mouse != self.config.ui.mouse {
+            set_mouse_capture(config.ui.mouse)?;
+        }
+        if config.user != self.config.user {
+            self.user = config.user.clone().unwrap_or_else(system_player);
+            self.update_best_scores();
+        }
+        let resize = config.ui.renderer != self.config.ui.renderer;
+        self.config = config;
+        self.update_fps();
+        // field size depends on renderer. Game in progress keeps its size, and
+        // new size is used from the next game
+        if resize && !self.game_in_progress() {
+            self.restart();
+        }
+        Ok(())
+    }
+    /// Record scores under this player from now on
+    fn select_player(&mut self, name: &str) {
+        self.user = name.to_string();
+        self.config.user = Some(name.to_string());
+        self.config.add_player(name);
+        self.update_best_scores();
+        self.save_settings();
+    }
+    /// Save current settings to config file
+    fn save_settings(&mut self) {
+        if let e @ Err(_) = self.config.save() {
+            self.error = Some(e);
+        }
+    }
+    /// Remember current settings in config file
+    fn save_config(&mut self) {
+        let config = Config {
+            difficulty: self.difficulty.prev,
+            show_achivements_grouped: self.show_achivements_grouped,
+            debug: self.debug,
+            ..self.config.clone()
+        };
+        if config == self.config {
+            return;
+        }
+        if let e @ Err(_) = config.save() {
+            self.error = Some(e);
+        }
+        self.config = config;
     }
 
     // -------- set game states --------
 
     fn exit(&mut self) {
-        self.state = AppState::Exit;
-    }
-    fn toggle_pause(&mut self) {
-        self.paused = !self.paused
+        self.exited = true;
     }
-    fn toggle_debug(&mut self) {
-        self.debug = !self.debug
+    fn pause(&mut self) {
+        self.paused = true;
+        self.resume_at = None;
     }
-    fn set_select_difficulty(&mut self) {
-        self.state = AppState::SelectDifficulty {
-            was_paused: self.paused,
-        };
+    /// Pause game in progress, while another screen is shown over it. Returns
+    /// whether game was paused, then it should be resumed when screen closes
+    fn pause_under_screen(&mut self) -> bool {
+        if self.paused || !self.game_in_progress() {
+            return false;
+        }
         self.pause();
+        true
     }
-    fn pause(&mut self) {
-        self.paused = true;
+    /// Unpause with countdown, so player has time to react
+    fn resume(&mut self) {
+        self.paused = false;
+        self.resume_at = Some(Instant::now() + RESUME_COUNTDOWN);
+    }
+    fn toggle_debug(&mut self) {
+        self.debug = !self.debug;
+        self.save_config();
     }
     fn unpause(&mut self) {
         self.paused = false;
+        self.resume_at = None;
     }
-    fn reset_app_state(&mut self) {
-        self.state = AppState::Play;
+    fn play_sound(&mut self, event: SoundEvent) {
+        self.sounds.play(&self.config.sound, event);
     }
     fn toggle_achivements_grouped(&mut self) {
         self.show_achivements_grouped = !self.show_achivements_grouped;
+        self.save_config();
     }
 
     // -------- set game values --------
 
     fn restart(&mut self) {
         self.scale_game_field();
-        self.game = Game::new(self.game_size);
+        self.too_small = !self.terminal_fits();
+        if self.too_small {
+            return;
+        }
+        // current game is abandoned
+        self.update_achivement();
         self.reset_difficulty();
+        let options = self
+            .difficulty
+            .mode()
+            .game_options(self.game_size, self.seed);
+        match Game::with_options(self.game_size, options) {
+            Ok(game) => self.game = game,
+            Err(e) => {
+                self.error = Some(Err(e.into()));
+                return;
+            }
+        }
+        self.difficulty.update_fps(0);
+        self.update_fps();
+        self.played = Duration::ZERO;
+        self.score_saved = false;
+        self.game_difficulty = (self.difficulty.kind, self.difficulty.mode());
         self.unpause();
-        self.reset_app_state();
+        self.update_best_scores();
+        self.badge_tracker = BadgeTracker::default();
+    }
+    fn update_best_scores(&mut self) {
+        let mode = self.difficulty.mode();
+        let scores = || {
+            self.achivements
+                .iter()
+                .filter(|a| a.difficulty == self.difficulty.kind && a.mode == mode)
+        };
+        let personal = scores()
+            .filter(|a| a.username == self.user)
+            .map(|a| a.score)
+            .max();
+        let global = scores().map(|a| a.score).max();
+        self.best_scores = (personal, global);
     }
     fn scale_game_field(&mut self) {
-        let (x, y) = self.block_size.into();
-        self.game_size = Pos::new(
-            (x as f64 / SCALE_SIZE.0) as CoordType,
-            (y as f64 / SCALE_SIZE.1) as CoordType,
-        );
+        self.game_size = self.fixed_size.unwrap_or_else(|| self.field_capacity());
     }
-    fn move_snake(&self) {
+    fn move_snake(&mut self) {
+        self.played += self.difficulty.fps.duration();
         self.game.move_snake();
+        self.update_badges();
     }
     fn rotate_snake(&self, to: MoveTo) {
         self.game.rotate_to(to);
     }
 }
 
+fn set_mouse_capture(enabled: bool) -> Result<()> {
+    if enabled {
+        std::io::stdout().execute(EnableMouseCapture)?;
+    } else {
+        std::io::stdout().execute(DisableMouseCapture)?;
+    }
+    Ok(())
+}
+
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
@@ -329,8 +860,23 @@ impl Widget for &App {
     {
         use crate::strings::tr::widgets::app as tr;
 
-        let title = Title::from(format!(" {} ", tr::title).bold());
-        let help = Title::from(self.keybind_help());
+        let title = Title::from(format!(" {} ", tr::title()).bold());
+        let parts = self.keybind_help();
+        let help = Line::from(parts.iter().map(|(s, _)| s.clone()).collect::<Vec<_>>());
+
+        // help is centered in bottom border
+        let inner = area.inner(Margin::new(1, 0));
+        let mut x = inner.x + inner.width.saturating_sub(help.width() as u16) / 2;
+        for (span, action) in parts {
+            let width = (span.width() as u16).min(inner.right().saturating_sub(x));
+            if let Some(action) = action {
+                let y = area.bottom().saturating_sub(1);
+                self.clickable(Rect::new(x, y, width, 1), Click::Action(action));
+            }
+            x += width;
+        }
+
+        let help = Title::from(help);
         Block::bordered()
             .title(title.alignment(Alignment::Center))
             .title(help.alignment(Alignment::Center).position(Position::Bottom))
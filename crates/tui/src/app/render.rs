@@ -1,27 +1,73 @@
+use std::rc::Rc;
+
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
+    layout::{Alignment, Constraint, Layout, Rect},
     style::Stylize,
-    text::Line,
-    widgets::{canvas::Canvas, Block, Padding, Paragraph, Widget},
+    text::Span,
+    widgets::{canvas::Canvas, Block, Padding, Paragraph, TableState, Widget},
     Frame,
 };
 
-use crate::{strings::tr, widgets};
+use lib::{Game, Pos};
+
+use crate::{
+    config::Renderer,
+    keys::Action,
+    strings::tr,
+    theme::ColorMode,
+    widgets::{self, SortBy},
+};
+
+use super::{App, Click, Keybind};
 
-use super::{App, DRAW_MARKER};
+/// Split frame into columns, and middle column into rows. Game field is in the
+/// center
+fn main_layout(area: Rect) -> (Rc<[Rect]>, Rc<[Rect]>) {
+    let contraints = [25, 50, 25].map(Constraint::Percentage);
+    let outer = Layout::horizontal(contraints).split(area);
+    let field = Layout::vertical(contraints).split(outer[1]);
+    (outer, field)
+}
+
+/// Area inside game field's borders
+pub(super) fn field_inner(area: Rect) -> Rect {
+    Block::bordered().inner(main_layout(area).1[1])
+}
 
 impl App {
     pub(super) fn render_frame(&self, frame: &mut Frame) {
+        self.clickable.borrow_mut().clear();
+        if self.too_small {
+            frame.render_widget(self.too_small_block(), frame.size());
+            return;
+        }
+
         frame.render_widget(self, frame.size());
 
+        // draw screens starting from the last one, which covers whole frame
+        let start = self
+            .screens
+            .iter()
+            .rposition(|s| !s.is_overlay())
+            .unwrap_or_default();
+        let help = self.clickable.borrow().len();
+        for screen in &self.screens[start..] {
+            // only the top screen reacts to clicks
+            self.clickable.borrow_mut().truncate(help);
+            screen.render(self, frame);
+        }
+
+        self.color_mode.apply(frame.buffer_mut());
+    }
+    /// Area in the center of frame, where game field is located
+    pub(super) fn center_area(&self, area: Rect) -> Rect {
+        main_layout(area).1[1]
+    }
+    /// Game field, score and achivements
+    pub(super) fn render_game(&self, frame: &mut Frame) {
         let contraints = [25, 50, 25].map(Constraint::Percentage);
-        let outer = Layout::horizontal(contraints).split(frame.size());
+        let (outer, field) = main_layout(frame.size());
         let debug = Layout::vertical(contraints).split(outer[0]);
-        let field = Layout::vertical(contraints).split(outer[1]);
-
-        let contraints = [50, 50].map(Constraint::Percentage);
-        let over_field = Layout::vertical(contraints).split(field[1]);
-        let over_field = Layout::horizontal(contraints).split(over_field[1]);
 
         let contraints = [26, 50].map(Constraint::Percentage);
         let achivements = Layout::vertical(contraints).split(outer[2]);
@@ -36,123 +82,192 @@ impl App {
         if self.debug {
             frame.render_widget(self.debug_block(), debug[1]);
         }
-        if self.selecting_difficulty() {
-            frame.render_widget(self.difficulty_select(), field[1]);
-        } else {
-            frame.render_widget(self.field_canvas(field[1]), field[1]);
-            frame.render_widget(self.finish_block(), over_field[1]);
-            frame.render_widget(self.achivements_block(), achivements[1]);
+        self.render_field(frame, field[1], &self.game);
+        self.render_toasts(frame, field[1]);
+        if let Some(secs) = self.countdown() {
+            self.render_countdown(frame, field[1], secs);
+        }
+        let mut state = TableState::default();
+        frame.render_stateful_widget(self.achivements_table(), achivements[1], &mut state);
+        let table = self.achivements_table();
+        let rows = table.rows();
+        for (i, area) in table.row_areas(achivements[1], state.offset()) {
+            // index in all achivements, as rows are filtered and sorted
+            let a = rows[i].1;
+            if let Some(i) = self.achivements.iter().position(|b| b == a) {
+                self.clickable(area, Click::Achivement(i));
+            }
+        }
+    }
+    /// Recently unlocked badges
+    fn render_toasts(&self, frame: &mut Frame, area: Rect) {
+        let toasts = self
+            .toasts
+            .iter()
+            .filter(|(_, t)| t.elapsed() < super::TOAST_TIME);
+        for (i, (badge, _)) in toasts.enumerate() {
+            let area = area.intersection(Rect {
+                y: area.y + i as u16 * 3,
+                ..area
+            });
+            let toast = widgets::Toast {
+                title: tr::badges::unlocked(),
+                text: badge.name(),
+            };
+            frame.render_widget(toast, area);
         }
     }
+    /// Seconds before game resumes, in the middle of field
+    fn render_countdown(&self, frame: &mut Frame, area: Rect, secs: u64) {
+        let [_, line, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let text = format!(" {secs} ").black().on_yellow().bold();
+        frame.render_widget(Paragraph::new(text).alignment(Alignment::Center), line);
+    }
+    /// Draw snake field with colors or symbols
+    pub(super) fn render_field(&self, frame: &mut Frame, area: Rect, game: &Game) {
+        match self.renderer() {
+            Renderer::Canvas => frame.render_widget(self.field_canvas(area, game), area),
+            Renderer::Cells => frame.render_widget(self.field_cells(game), area),
+            Renderer::HalfBlock => frame.render_widget(self.field_half_blocks(game), area),
+        }
+    }
+    /// Size of area needed to draw field of `size` blocks, including borders
+    pub(super) fn field_area_size(&self, size: Pos) -> (u16, u16) {
+        let (x, y) = size.into();
+        let (x, y) = match self.renderer() {
+            Renderer::HalfBlock => (x, y.div_ceil(2)),
+            Renderer::Canvas | Renderer::Cells => (x * 2, y),
+        };
+        (x as u16 + 2, y as u16 + 2)
+    }
     /// Canvas with snake field
-    fn field_canvas(&self, size: Rect) -> impl Widget + '_ {
+    fn field_canvas<'a>(&'a self, size: Rect, game: &'a Game) -> impl Widget + 'a {
         Canvas::default()
-            .block(Block::bordered().cyan())
+            .block(Block::bordered().fg(self.theme.border))
             .x_bounds([0.0, size.width as f64])
             .y_bounds([0.0, size.height as f64])
-            .marker(DRAW_MARKER)
+            .marker(self.config.ui.marker)
             .paint(|ctx| {
                 ctx.draw(&widgets::SnakeField::new(
-                    self.game.snake(),
-                    self.game.food(),
+                    game.snake(),
+                    game.food(),
+                    &self.theme,
                 ))
             })
     }
+    /// Snake field drawn with symbols
+    fn field_cells(&self, game: &Game) -> impl Widget + '_ {
+        widgets::SnakeCells {
+            snake: game.snake(),
+            food: game.food(),
+            direction: game.direction(),
+            size: game.size(),
+            theme: self.theme,
+            ascii: self.color_mode == ColorMode::Ascii,
+            block: Some(Block::bordered().fg(self.theme.border)),
+        }
+    }
+    /// Snake field with square blocks
+    fn field_half_blocks(&self, game: &Game) -> impl Widget + '_ {
+        widgets::SnakeHalfBlocks {
+            snake: game.snake(),
+            food: game.food(),
+            theme: self.theme,
+            block: Some(Block::bordered().fg(self.theme.border)),
+        }
+    }
     /// Game info + debug info
     fn info_block(&self) -> impl Widget + '_ {
         widgets::Info {
             difficulty: self.difficulty.clone(),
             stats: self.game.stats(),
-            show_pause: self.paused && !self.selecting_difficulty(),
+            show_pause: self.paused,
         }
     }
     fn debug_block(&self) -> impl Widget + '_ {
         use tr::widgets::debug as tr;
 
         let text = vec![
-            format!("{}: {}", tr::block_size, self.block_size).into(),
-            format!("{}: {}", tr::field_size, self.game_size).into(),
-            format!("{}: {}", tr::food, self.game.food()).into(),
-            format!("{}: {}", tr::snake_head, self.game.head()).into(),
-            format!("{}:", tr::fps).into(),
+            format!("{}: {}", tr::block_size(), self.block_size).into(),
+            format!("{}: {}", tr::field_size(), self.game_size).into(),
+            format!("{}: {}", tr::food(), self.game.food()).into(),
+            format!("{}: {}", tr::snake_head(), self.game.head()).into(),
+            format!("{}:", tr::fps()).into(),
             format!("  {}", self.debug_info.fps).into(),
-            format!("{}: {}", tr::snake_direction, self.game.direction()).into(),
+            format!("{}: {}", tr::snake_direction(), self.game.direction()).into(),
         ];
         Paragraph::new(text).block(Block::new().padding(Padding::uniform(1)))
     }
     /// Block with difficulty select
-    fn difficulty_select(&self) -> impl Widget + '_ {
+    pub(super) fn difficulty_select(&self) -> widgets::DifficultySelect {
         widgets::DifficultySelect {
             difficulty: self.difficulty.kind,
             difficulty_changed: self.difficulty_changed(),
+            submit_key: self.keys.key(Action::Submit),
+            cancel_key: self.keys.key(Action::Difficulty),
         }
     }
-    /// Block with achivements. Only for current difficulty
-    fn achivements_block(&self) -> impl Widget + '_ {
+    /// Achivements next to game field. Only for current difficulty and mode,
+    /// or of all difficulties sorted by user
+    fn achivements_table(&self) -> widgets::Achivements<'_> {
+        let (difficulty, mode, sort) = if self.show_achivements_grouped {
+            let mode = self.difficulty.mode();
+            (Some(self.difficulty.kind), Some(mode), SortBy::Score)
+        } else {
+            (None, None, SortBy::User)
+        };
         widgets::Achivements {
-            difficulty: self.difficulty.kind,
-            show_achivements_grouped: self.show_achivements_grouped,
             achivements: &self.achivements,
-            achivements_map: &self.achivements_map,
+            user: &self.user,
+            difficulty,
+            mode,
+            sort,
+            compact: true,
         }
     }
-    fn finish_block(&self) -> impl Widget + '_ {
-        use widgets::FinishState;
-        let state = match self.game.stats().status {
-            lib::GameStatus::Play => None,
-            lib::GameStatus::Fail => Some(FinishState::Fail),
-            lib::GameStatus::Win => Some(FinishState::Win),
-        };
-        widgets::Finish { state }
+    fn too_small_block(&self) -> impl Widget + '_ {
+        widgets::TooSmall {
+            size: self.block_size,
+            required: self.required_size(),
+        }
     }
-
     // -------- render utilities --------
 
-    pub(super) fn keybind_help(&self) -> Line<'_> {
-        use tr::keybind as tr;
+    /// Parts of help bar, with actions triggered by clicking them
+    pub(super) fn keybind_help(&self) -> Vec<(Span<'static>, Option<Action>)> {
+        let takes_text = self.screens.last().is_some_and(|s| s.takes_text());
+        let mut keybinds = vec![];
+        // help goes first, so it is visible even when other keys do not fit
+        if !takes_text {
+            keybinds.push(Keybind::new(tr::keybind::help(), [Action::Help]));
+        }
+        keybinds.extend(self.keybinds());
+        if !takes_text {
+            keybinds.extend(self.global_keybinds(self.debug));
+        }
 
+        const SP: &str = " ";
+        const SEP: &str = "|";
         let mut instructions = vec![];
-        let mut show_keybind = |name: &'static str, key: &'static str, sep| {
-            const SP: &str = " ";
-            const SEP: &str = "|";
-            instructions.extend(vec![
-                SP.into(),
-                name.into(),
-                SP.into(),
-                key.blue().bold(),
-                SP.into(),
-            ]);
-            if sep {
-                instructions.push(SEP.into());
+        for (i, keybind) in keybinds.into_iter().enumerate() {
+            if i > 0 {
+                instructions.push((SEP.into(), None));
             }
-        };
-        if self.playing() && !self.paused {
-            show_keybind(tr::r#move, "← ↑ → ↓", true);
-        } else if self.selecting_difficulty() {
-            show_keybind(tr::select, "← →", true);
-            show_keybind(tr::submit, "Enter", true);
-            show_keybind(tr::cancel, "d", true);
-        }
-        if self.playing() && !self.game_ended() {
-            if !self.paused {
-                show_keybind(tr::pause, "Esc", true);
-            } else if self.paused {
-                show_keybind(tr::resume, "Esc", true);
-            }
-        }
-        if !self.selecting_difficulty() {
-            if self.show_achivements_grouped {
-                show_keybind(tr::achivements_by_user, "a", true);
-            } else {
-                show_keybind(tr::achivements_summary, "a", true);
+            let first = keybind.actions.first().copied();
+            instructions.extend([(SP.into(), None), (keybind.name.into(), first)]);
+            for &action in &keybind.actions {
+                instructions.extend([
+                    (SP.into(), None),
+                    (self.keys.key(action).blue().bold(), Some(action)),
+                ]);
             }
-            show_keybind(tr::difficulty, "d", true);
-        }
-        show_keybind(tr::restart, "r", true);
-        if self.debug {
-            show_keybind(tr::debug, "F3", true);
+            instructions.push((SP.into(), None));
         }
-        show_keybind(tr::quit, "q", false);
-        Line::from(instructions)
+        instructions
     }
 }
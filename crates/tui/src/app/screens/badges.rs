@@ -0,0 +1,55 @@
+use ratatui::{
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Padding, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{badges::Badge, keys::Action, strings::tr};
+
+use super::{App, Keybind, Screen, Transition};
+
+/// All badges of current player, locked and unlocked
+#[derive(Debug, Default)]
+pub(in crate::app) struct BadgesScreen;
+
+impl Screen for BadgesScreen {
+    fn handle_action(&mut self, _app: &mut App, action: Action) -> Transition {
+        match action {
+            Action::Back => Transition::Pop,
+            _ => Transition::None,
+        }
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::badges as tr;
+
+        let mut text: Vec<Line> = vec![tr::title().bold().into(), "".into()];
+        for badge in Badge::ALL {
+            let unlock = app
+                .badges
+                .iter()
+                .find(|u| u.username == app.user && u.badge == badge);
+            let line: Line = match unlock {
+                Some(u) => vec![
+                    format!("[x] {}", badge.name()).green().bold(),
+                    format!(" ({})", u.date.format("%Y-%m-%d")).into(),
+                ]
+                .into(),
+                None => vec![
+                    format!("[ ] {}", badge.name()).dark_gray(),
+                    format!(" ({})", tr::locked()).dark_gray(),
+                ]
+                .into(),
+            };
+            text.extend([line, format!("    {}", badge.description()).into()]);
+        }
+
+        let paragraph = Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(Block::new().padding(Padding::uniform(1)));
+        frame.render_widget(paragraph, app.center_area(frame.size()));
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        vec![Keybind::new(tr::keybind::back(), [Action::Back])]
+    }
+}
@@ -0,0 +1,266 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    config::validate_preset,
+    difficulty::{CustomRules, GameMode, SpeedUp, StartDirection},
+    keys::{Action, KeyContext},
+    strings::{format_spans, tr},
+};
+
+use super::{
+    cycle, cycle_filter, cycle_index, typed_char, App, Click, Keybind, Screen, Transition,
+};
+
+/// Editor of custom difficulty, opened from difficulty selection. Rules are
+/// saved as named presets
+#[derive(Debug, Default)]
+pub(in crate::app) struct CustomScreen {
+    selected: usize,
+    /// Edited preset, `None` for a new one
+    preset: Option<String>,
+    rules: CustomRules,
+    /// Name of new preset, while it is typed
+    name: Option<String>,
+    /// Why preset can't be saved
+    error: Option<String>,
+    /// Game was paused when screen opened
+    paused_game: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Preset,
+    Speed,
+    Length,
+    Direction,
+    Growth,
+    GrowthDelay,
+    Walls,
+    SpeedUp,
+}
+
+impl Row {
+    const ALL: [Self; 8] = [
+        Self::Preset,
+        Self::Speed,
+        Self::Length,
+        Self::Direction,
+        Self::Growth,
+        Self::GrowthDelay,
+        Self::Walls,
+        Self::SpeedUp,
+    ];
+
+    fn name(self) -> &'static str {
+        use tr::widgets::custom as tr;
+
+        match self {
+            Self::Preset => tr::preset(),
+            Self::Speed => tr::speed(),
+            Self::Length => tr::length(),
+            Self::Direction => tr::direction(),
+            Self::Growth => tr::growth(),
+            Self::GrowthDelay => tr::growth_delay(),
+            Self::Walls => tr::walls(),
+            Self::SpeedUp => tr::speed_up(),
+        }
+    }
+}
+
+impl CustomScreen {
+    fn row(&self) -> Row {
+        Row::ALL[self.selected]
+    }
+    fn value(&self, row: Row) -> String {
+        use tr::widgets::custom as tr;
+
+        let rules = &self.rules;
+        match row {
+            Row::Preset => self
+                .preset
+                .clone()
+                .unwrap_or_else(|| tr::new_preset().to_string()),
+            Row::Speed => rules.moves_per_second.to_string(),
+            Row::Length => rules.initial_length.to_string(),
+            Row::Direction => rules.direction.to_string(),
+            Row::Growth => rules.growth.to_string(),
+            Row::GrowthDelay => rules.growth_delay.to_string(),
+            Row::Walls if rules.walls_kill => tr::walls_kill().to_string(),
+            Row::Walls => tr::walls_wrap().to_string(),
+            Row::SpeedUp => rules.speed_up.to_string(),
+        }
+    }
+    /// Select next or previous value
+    fn change(&mut self, app: &App, forward: bool) {
+        let step = if forward { 1 } else { -1 };
+        let row = self.row();
+        let rules = &mut self.rules;
+        match row {
+            Row::Preset => {
+                let presets: Vec<_> = app.config.presets.keys().cloned().collect();
+                self.preset = cycle_filter(&presets, &self.preset, forward);
+                if let Some(p) = &self.preset {
+                    self.rules = app.config.presets[p];
+                }
+            }
+            Row::Speed => rules.add_speed(step),
+            Row::Length => rules.add_length(step as isize),
+            Row::Direction => {
+                rules.direction = cycle(&StartDirection::ALL, &rules.direction, forward)
+            }
+            Row::Growth => rules.add_growth(step as isize),
+            Row::GrowthDelay => rules.add_growth_delay(step as isize),
+            Row::Walls => rules.walls_kill = !rules.walls_kill,
+            Row::SpeedUp => rules.speed_up = cycle(&SpeedUp::ALL, &rules.speed_up, forward),
+        }
+    }
+    fn submit(&mut self, app: &mut App, name: &str) -> Transition {
+        if let Err(e) = validate_preset(name) {
+            self.error = Some(e.to_string());
+            return Transition::None;
+        }
+        app.submit_custom(name, self.rules);
+        Transition::Pop
+    }
+    /// Areas of rows, for clicking them
+    fn row_areas(area: Rect) -> impl Iterator<Item = Rect> {
+        // after title and empty line
+        (0..Row::ALL.len() as u16)
+            .map(move |i| Rect::new(area.x, area.y + 2 + i, area.width, 1))
+            .filter(move |r| r.bottom() <= area.bottom())
+    }
+}
+
+impl Screen for CustomScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        self.preset = app.config.preset.clone();
+        self.rules = app.config.custom_rules();
+        self.paused_game = app.pause_under_screen();
+    }
+    fn on_exit(&mut self, app: &mut App) {
+        if self.paused_game {
+            app.resume();
+        }
+    }
+    fn handle_key(&mut self, app: &mut App, event: KeyEvent) -> Option<Transition> {
+        let name = self.name.as_mut()?;
+        if let Some(c) = typed_char(event) {
+            name.push(c);
+            return Some(Transition::None);
+        }
+        let t = match event.code {
+            KeyCode::Backspace => {
+                name.pop();
+                Transition::None
+            }
+            code => match app.keys.action(KeyContext::Menu, code) {
+                Some(Action::Submit) => {
+                    let name = name.trim().to_string();
+                    self.submit(app, &name)
+                }
+                Some(Action::Back) => {
+                    self.name = None;
+                    self.error = None;
+                    Transition::None
+                }
+                _ => Transition::None,
+            },
+        };
+        Some(t)
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let len = Row::ALL.len();
+        match action {
+            Action::MoveUp | Action::MoveDown => {
+                self.selected = cycle_index(self.selected, len, action == Action::MoveDown)
+            }
+            Action::MoveLeft | Action::MoveRight => {
+                self.change(app, action == Action::MoveRight);
+            }
+            Action::Submit => match self.preset.clone() {
+                Some(name) => return self.submit(app, &name),
+                None => self.name = Some(String::new()),
+            },
+            Action::Back => {
+                app.reset_difficulty();
+                return Transition::Pop;
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+    fn handle_click(&mut self, _app: &mut App, item: usize) -> Transition {
+        self.selected = item;
+        Transition::None
+    }
+    fn takes_text(&self) -> bool {
+        self.name.is_some()
+    }
+    fn is_overlay(&self) -> bool {
+        true
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::widgets::custom as tr;
+
+        let area = app.center_area(frame.size());
+        frame.render_widget(Clear, area);
+
+        let mut text: Vec<Line> = vec![tr::title().bold().into(), "".into()];
+        text.extend(Row::ALL.iter().enumerate().map(|(i, &row)| {
+            let name = format!("{}: ", row.name());
+            if i != self.selected {
+                return vec![name.into(), self.value(row).blue()].into();
+            }
+            let value = format!("< {} >", self.value(row));
+            vec![name.bold(), value.blue().bold()].into()
+        }));
+        let mode = GameMode::Custom(self.rules).to_string();
+        text.extend([
+            "".into(),
+            format_spans(tr::scores(), [("mode", mode.dark_gray())]).into(),
+        ]);
+        if let Some(name) = &self.name {
+            text.extend([
+                "".into(),
+                vec![
+                    format!("{}: ", tr::name()).into(),
+                    format!("{name}_").blue(),
+                ]
+                .into(),
+            ]);
+        }
+        if let Some(error) = &self.error {
+            text.extend(["".into(), error.clone().red().into()]);
+        }
+        if self.name.is_none() {
+            for (i, row) in Self::row_areas(area).enumerate() {
+                app.clickable(row, Click::Item(i));
+            }
+        }
+        let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        if self.name.is_some() {
+            return vec![
+                Keybind::new(tr::submit(), [Action::Submit]),
+                Keybind::new(tr::cancel(), [Action::Back]),
+            ];
+        }
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::change(), [Action::MoveLeft, Action::MoveRight]),
+            Keybind::new(tr::save(), [Action::Submit]),
+            Keybind::new(tr::cancel(), [Action::Back]),
+        ]
+    }
+}
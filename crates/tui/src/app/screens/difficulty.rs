@@ -0,0 +1,82 @@
+use ratatui::{widgets::Clear, Frame};
+
+use crate::{
+    difficulty::{DifficultyKind, DIFFICULTIES},
+    keys::{Action, KeyContext},
+    strings::tr,
+};
+
+use super::{App, Click, CustomScreen, Keybind, Screen, Transition};
+
+/// Difficulty selection, shown over game field
+#[derive(Debug, Default)]
+pub(in crate::app) struct DifficultyScreen {
+    /// Game was paused when screen opened
+    paused_game: bool,
+}
+
+impl Screen for DifficultyScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        app.reset_difficulty();
+        self.paused_game = app.pause_under_screen();
+    }
+    fn on_exit(&mut self, app: &mut App) {
+        if self.paused_game {
+            app.resume();
+        }
+    }
+    fn context(&self) -> KeyContext {
+        KeyContext::SelectDifficulty
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        match action {
+            Action::Secret => app.select_difficulty(DifficultyKind::Secret),
+            Action::MoveLeft => app.select_difficulty(app.difficulty.kind.prev()),
+            Action::MoveRight => app.select_difficulty(app.difficulty.kind.next()),
+            Action::Difficulty | Action::Back => {
+                app.reset_difficulty();
+                return Transition::Pop;
+            }
+            // rules are chosen before game restarts
+            Action::Submit if app.difficulty.kind == DifficultyKind::Custom => {
+                return Transition::replace(CustomScreen::default());
+            }
+            Action::Submit => {
+                app.submit_difficulty();
+                return Transition::Pop;
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+    /// First click selects difficulty, second one submits it
+    fn handle_click(&mut self, app: &mut App, item: usize) -> Transition {
+        let difficulty = DIFFICULTIES[item];
+        if difficulty == app.difficulty.kind {
+            return self.handle_action(app, Action::Submit);
+        }
+        app.select_difficulty(difficulty);
+        Transition::None
+    }
+    fn is_overlay(&self) -> bool {
+        true
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        let area = app.center_area(frame.size());
+        frame.render_widget(Clear, area);
+        let select = app.difficulty_select();
+        for (i, item) in select.difficulty_areas(area).into_iter().enumerate() {
+            app.clickable(item, Click::Item(i));
+        }
+        frame.render_widget(select, area);
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::select(), [Action::MoveLeft, Action::MoveRight]),
+            Keybind::new(tr::submit(), [Action::Submit]),
+            Keybind::new(tr::cancel(), [Action::Difficulty]),
+        ]
+    }
+}
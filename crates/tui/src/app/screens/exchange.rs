@@ -0,0 +1,184 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Alignment,
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    achive::{export_achivements, import_achivements, ExportFormat},
+    keys::{Action, KeyContext},
+    strings::{self, tr},
+};
+
+use super::{cycle_index, typed_char, App, Keybind, Screen, Transition};
+
+/// Export and import of scores, for moving them between machines
+#[derive(Debug, Default)]
+pub(in crate::app) struct ExchangeScreen {
+    selected: usize,
+    /// File name, while it is entered
+    path: Option<String>,
+    /// Result of last operation
+    message: Option<Result<String, String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    ExportJson,
+    ExportCsv,
+    Import,
+}
+
+impl Operation {
+    const ALL: [Self; 3] = [Self::ExportJson, Self::ExportCsv, Self::Import];
+
+    fn name(self) -> &'static str {
+        use tr::widgets::exchange as tr;
+
+        match self {
+            Self::ExportJson => tr::export_json(),
+            Self::ExportCsv => tr::export_csv(),
+            Self::Import => tr::import(),
+        }
+    }
+    /// Suggested file name
+    fn default_path(self) -> &'static str {
+        match self {
+            Self::ExportJson => "snaks-scores.json",
+            Self::ExportCsv => "snaks-scores.csv",
+            Self::Import => "",
+        }
+    }
+    fn run(self, app: &mut App, path: &Path) -> Result<String> {
+        use tr::widgets::exchange as tr;
+
+        let format = match self {
+            Self::ExportJson => ExportFormat::Json,
+            Self::ExportCsv => ExportFormat::Csv,
+            Self::Import => {
+                let changed = import_achivements(path, ExportFormat::from_path(path))?;
+                app.read_achivement();
+                return Ok(tr::imported(changed));
+            }
+        };
+        let text = export_achivements(&app.achivements, format)?;
+        std::fs::write(path, text)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(strings::format(
+            tr::exported(),
+            &[("path", path.display().to_string())],
+        ))
+    }
+}
+
+impl ExchangeScreen {
+    fn operation(&self) -> Operation {
+        Operation::ALL[self.selected]
+    }
+    fn submit(&mut self, app: &mut App) {
+        let Some(path) = &self.path else {
+            self.path = Some(self.operation().default_path().to_string());
+            self.message = None;
+            return;
+        };
+        let path = path.trim().to_string();
+        if path.is_empty() {
+            return;
+        }
+        self.path = None;
+        self.message = Some(
+            self.operation()
+                .run(app, Path::new(&path))
+                .map_err(|e| format!("{e:#}")),
+        );
+    }
+}
+
+impl Screen for ExchangeScreen {
+    fn handle_key(&mut self, app: &mut App, event: KeyEvent) -> Option<Transition> {
+        let path = self.path.as_mut()?;
+        if let Some(c) = typed_char(event) {
+            path.push(c);
+            return Some(Transition::None);
+        }
+        match event.code {
+            KeyCode::Backspace => {
+                path.pop();
+            }
+            code => match app.keys.action(KeyContext::Menu, code) {
+                Some(Action::Submit) => self.submit(app),
+                Some(Action::Back) => self.path = None,
+                _ => {}
+            },
+        }
+        Some(Transition::None)
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let len = Operation::ALL.len();
+        match action {
+            Action::MoveUp | Action::MoveDown => {
+                self.selected = cycle_index(self.selected, len, action == Action::MoveDown)
+            }
+            Action::Submit => self.submit(app),
+            Action::Back => return Transition::Pop,
+            _ => {}
+        }
+        Transition::None
+    }
+    fn takes_text(&self) -> bool {
+        self.path.is_some()
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::widgets::exchange as tr;
+
+        let mut text: Vec<Line> = vec![tr::title().bold().into(), "".into()];
+        text.extend(Operation::ALL.iter().enumerate().map(|(i, op)| {
+            if i == self.selected {
+                format!("> {} <", op.name()).blue().bold().into()
+            } else {
+                op.name().into()
+            }
+        }));
+        if let Some(path) = &self.path {
+            text.extend([
+                "".into(),
+                vec![
+                    format!("{}: ", tr::file()).into(),
+                    format!("{path}_").blue(),
+                ]
+                .into(),
+            ]);
+        }
+        match &self.message {
+            Some(Ok(msg)) => text.extend(["".into(), msg.clone().green().into()]),
+            Some(Err(e)) => text.extend(["".into(), e.clone().red().into()]),
+            None => {}
+        }
+
+        let paragraph = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, app.center_area(frame.size()));
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        if self.path.is_some() {
+            return vec![
+                Keybind::new(tr::submit(), [Action::Submit]),
+                Keybind::new(tr::cancel(), [Action::Back]),
+            ];
+        }
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::submit(), [Action::Submit]),
+            Keybind::new(tr::back(), [Action::Back]),
+        ]
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use ratatui::Frame;
+
+use crate::{
+    keys::{Action, ControlScheme, KeyContext},
+    strings::tr,
+};
+
+use super::{App, DifficultyScreen, GameOverScreen, Keybind, PauseScreen, Screen, Transition};
+
+/// Game field with score and achivements
+#[derive(Debug, Default)]
+pub(in crate::app) struct GameScreen;
+
+impl Screen for GameScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        app.restart();
+    }
+    fn context(&self) -> KeyContext {
+        KeyContext::Play
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        match action {
+            Action::Restart => app.restart(),
+            Action::Difficulty => return Transition::push(DifficultyScreen::default()),
+            Action::Achivements => app.toggle_achivements_grouped(),
+            Action::Menu => return Transition::Pop,
+            _ => {}
+        }
+
+        if app.game_ended() {
+            return Transition::None;
+        }
+
+        // keys when playing except fail/win
+        if action == Action::Pause {
+            return Transition::push(PauseScreen::default());
+        }
+
+        // keys for snake rotate, also during countdown
+        let scheme = app.config.keys.scheme;
+        if let Some(to) = scheme.direction(action, app.game.direction()) {
+            app.rotate_snake(to);
+        }
+        Transition::None
+    }
+    fn tick(&mut self, app: &mut App) -> Transition {
+        if app.game_ended() {
+            return Transition::push(GameOverScreen::new(app.game_summary()));
+        }
+        if !app.paused && app.countdown().is_none() {
+            app.move_snake();
+        }
+        Transition::None
+    }
+    fn on_focus_lost(&mut self, app: &mut App) -> Transition {
+        if app.game_ended() {
+            return Transition::None;
+        }
+        Transition::push(PauseScreen::default())
+    }
+    fn tick_duration(&self, app: &App) -> Duration {
+        app.difficulty.fps.duration()
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        app.render_game(frame);
+    }
+    fn keybinds(&self, app: &App) -> Vec<Keybind> {
+        Self::game_keybinds(app, false)
+    }
+}
+
+impl GameScreen {
+    /// Keys of game. With `all`, keys which are not available now are
+    /// included
+    pub(super) fn game_keybinds(app: &App, all: bool) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        let mut res = vec![];
+        if all || !app.game_ended() {
+            let moves = match app.config.keys.scheme {
+                ControlScheme::Absolute => vec![
+                    Action::MoveLeft,
+                    Action::MoveUp,
+                    Action::MoveRight,
+                    Action::MoveDown,
+                ],
+                ControlScheme::Relative => vec![Action::MoveLeft, Action::MoveRight],
+            };
+            res.push(Keybind::new(tr::r#move(), moves));
+        }
+        if all || !app.game_ended() {
+            res.push(Keybind::new(tr::pause(), [Action::Pause]));
+        }
+        let name = if app.show_achivements_grouped {
+            tr::achivements_by_user()
+        } else {
+            tr::achivements_summary()
+        };
+        res.extend([
+            Keybind::new(name, [Action::Achivements]),
+            Keybind::new(tr::difficulty(), [Action::Difficulty]),
+            Keybind::new(tr::restart(), [Action::Restart]),
+            Keybind::new(tr::menu(), [Action::Menu]),
+        ]);
+        res
+    }
+}
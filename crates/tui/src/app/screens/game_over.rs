@@ -0,0 +1,97 @@
+use ratatui::Frame;
+
+use crate::{keys::Action, strings::tr, widgets};
+
+use super::{cycle_index, App, Click, DifficultyScreen, Keybind, Screen, Transition};
+
+/// Results of finished game, shown over game field
+#[derive(Debug)]
+pub(in crate::app) struct GameOverScreen {
+    summary: widgets::GameSummary,
+    selected: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum GameOverItem {
+    Retry,
+    Difficulty,
+    Quit,
+}
+
+impl GameOverItem {
+    const ALL: [Self; 3] = [Self::Retry, Self::Difficulty, Self::Quit];
+
+    fn name(self) -> &'static str {
+        use tr::widgets::finish as tr;
+
+        match self {
+            Self::Retry => tr::retry(),
+            Self::Difficulty => tr::change_difficulty(),
+            Self::Quit => tr::quit(),
+        }
+    }
+}
+
+impl GameOverScreen {
+    pub(in crate::app) fn new(summary: widgets::GameSummary) -> Self {
+        Self {
+            summary,
+            selected: 0,
+        }
+    }
+}
+
+impl Screen for GameOverScreen {
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let len = GameOverItem::ALL.len();
+        match action {
+            Action::MoveUp | Action::MoveDown => {
+                self.selected = cycle_index(self.selected, len, action == Action::MoveDown)
+            }
+            Action::Submit => {
+                return match GameOverItem::ALL[self.selected] {
+                    GameOverItem::Retry => {
+                        app.restart();
+                        Transition::Pop
+                    }
+                    // picker can be cancelled, and finished game should not
+                    // be shown again
+                    GameOverItem::Difficulty => {
+                        app.restart();
+                        Transition::replace(DifficultyScreen::default())
+                    }
+                    GameOverItem::Quit => Transition::Quit,
+                };
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+    fn is_overlay(&self) -> bool {
+        true
+    }
+    fn handle_click(&mut self, app: &mut App, item: usize) -> Transition {
+        self.selected = item;
+        self.handle_action(app, Action::Submit)
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        let finish = widgets::Finish {
+            summary: &self.summary,
+            items: GameOverItem::ALL.map(GameOverItem::name).to_vec(),
+            selected: self.selected,
+        };
+        let area = app.center_area(frame.size());
+        for (i, item) in finish.item_areas(area).into_iter().enumerate() {
+            app.clickable(item, Click::Item(i));
+        }
+        frame.render_widget(finish, area);
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::submit(), [Action::Submit]),
+        ]
+    }
+}
@@ -0,0 +1,142 @@
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    style::Stylize,
+    text::Line,
+    widgets::{
+        Block, Clear, Padding, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
+    },
+    Frame,
+};
+
+use crate::{
+    difficulty::DIFFICULTIES,
+    keys::{Action, KeyContext},
+    strings::tr,
+};
+
+use super::{App, Keybind, Screen, Transition};
+
+/// Lines scrolled by page keys
+const PAGE: u16 = 10;
+
+/// Scrollable keybindings and rules, shown over current screen
+#[derive(Debug, Default)]
+pub(in crate::app) struct HelpScreen {
+    /// Keys of screen for which help is shown
+    keybinds: Vec<Keybind>,
+    scroll: u16,
+    /// Game was paused when screen opened
+    paused_game: bool,
+}
+
+impl HelpScreen {
+    pub(in crate::app) fn new(keybinds: Vec<Keybind>) -> Self {
+        Self {
+            keybinds,
+            ..Default::default()
+        }
+    }
+    fn text(&self, app: &App) -> Vec<Line<'static>> {
+        use tr::widgets::help as tr;
+
+        let mut text: Vec<Line> = vec![tr::keys().bold().into(), "".into()];
+        text.extend(self.keybinds.iter().map(|keybind| {
+            let keys: Vec<_> = keybind
+                .actions
+                .iter()
+                .map(|&a| app.keys.keys(a).join("/"))
+                .collect();
+            vec![
+                format!("{}: ", keybind.name).into(),
+                keys.join(" ").blue().bold(),
+            ]
+            .into()
+        }));
+
+        text.extend(["".into(), tr::rules().bold().into(), "".into()]);
+        let rules = [
+            tr::rule_food(),
+            tr::rule_wrap(),
+            tr::rule_reverse(),
+            tr::rule_collide(),
+            tr::rule_pause(),
+        ];
+        text.extend(rules.map(|r| format!("- {r}").into()));
+
+        text.extend(["".into(), tr::difficulties().bold().into(), "".into()]);
+        text.push(format!("{}:", tr::difficulty_speed()).into());
+        text.extend(DIFFICULTIES.into_iter().filter_map(|d| {
+            let speed = d.moves_per_second()?;
+            Some(vec![format!("- {d}: ").into(), speed.to_string().blue()].into())
+        }));
+        text.push(format!("- {}", tr::difficulty_custom()).into());
+        text.push(tr::difficulty_restart().into());
+        text
+    }
+}
+
+impl Screen for HelpScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        self.paused_game = app.pause_under_screen();
+    }
+    fn on_exit(&mut self, app: &mut App) {
+        if self.paused_game {
+            app.resume();
+        }
+    }
+    fn context(&self) -> KeyContext {
+        KeyContext::Help
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let max = self.text(app).len().saturating_sub(1) as u16;
+        match action {
+            Action::MoveUp => self.scroll = self.scroll.saturating_sub(1),
+            Action::MoveDown => self.scroll = (self.scroll + 1).min(max),
+            Action::PageUp => self.scroll = self.scroll.saturating_sub(PAGE),
+            Action::PageDown => self.scroll = (self.scroll + PAGE).min(max),
+            Action::Back | Action::Help => return Transition::Pop,
+            _ => {}
+        }
+        Transition::None
+    }
+    fn is_overlay(&self) -> bool {
+        true
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        let text = self.text(app);
+        let lines = text.len();
+
+        let area = frame.size();
+        let [area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(80)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let block = Block::bordered()
+            .title(format!(" {} ", tr::widgets::help::title()).bold())
+            .padding(Padding::horizontal(1));
+        let paragraph = Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .block(block);
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+
+        let mut state = ScrollbarState::new(lines).position(self.scroll as usize);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            area,
+            &mut state,
+        );
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::scroll(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::close(), [Action::Back]),
+        ]
+    }
+}
@@ -0,0 +1,147 @@
+use std::cell::Cell;
+
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, TableState},
+    Frame,
+};
+
+use crate::{
+    achive::Achivement,
+    difficulty::{DifficultyKind, GameMode},
+    keys::Action,
+    strings::tr,
+    widgets::{Achivements, SortBy},
+};
+
+use super::{cycle, cycle_filter, App, Click, Keybind, Screen, Transition};
+
+/// Table of all achivements
+#[derive(Debug, Default)]
+pub(in crate::app) struct LeaderboardScreen {
+    selected: usize,
+    /// First visible row, kept between renders
+    offset: Cell<usize>,
+    /// Number of visible rows on last render, for paging
+    page: Cell<usize>,
+    sort: SortBy,
+    difficulty: Option<DifficultyKind>,
+    mode: Option<GameMode>,
+}
+
+impl LeaderboardScreen {
+    /// Leaderboard of achivement's difficulty and mode, with it selected
+    pub(in crate::app) fn showing(app: &App, achivement: &Achivement) -> Self {
+        let mut screen = Self {
+            difficulty: Some(achivement.difficulty),
+            mode: Some(achivement.mode),
+            ..Default::default()
+        };
+        let rows = screen.table(app).rows();
+        screen.selected = rows
+            .iter()
+            .position(|(_, a)| *a == achivement)
+            .unwrap_or_default();
+        screen
+    }
+    fn table<'a>(&self, app: &'a App) -> Achivements<'a> {
+        Achivements {
+            achivements: &app.achivements,
+            user: &app.user,
+            difficulty: self.difficulty,
+            mode: self.mode,
+            sort: self.sort,
+            compact: false,
+        }
+    }
+    fn scroll(&mut self, app: &App, rows: isize) {
+        let max = self.table(app).rows().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(rows).min(max);
+    }
+}
+
+impl Screen for LeaderboardScreen {
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let page = self.page.get().max(1) as isize;
+        match action {
+            Action::MoveUp => self.scroll(app, -1),
+            Action::MoveDown => self.scroll(app, 1),
+            Action::PageUp => self.scroll(app, -page),
+            Action::PageDown => self.scroll(app, page),
+            Action::MoveLeft | Action::MoveRight => {
+                let mut difficulties: Vec<_> =
+                    app.achivements.iter().map(|a| a.difficulty).collect();
+                difficulties.sort();
+                difficulties.dedup();
+                let forward = action == Action::MoveRight;
+                self.difficulty = cycle_filter(&difficulties, &self.difficulty, forward);
+                self.selected = 0;
+            }
+            Action::Filter => {
+                let mut modes: Vec<_> = app.achivements.iter().map(|a| a.mode).collect();
+                modes.sort();
+                modes.dedup();
+                self.mode = cycle_filter(&modes, &self.mode, true);
+                self.selected = 0;
+            }
+            Action::Sort => {
+                self.sort = cycle(&SortBy::ALL, &self.sort, true);
+                self.selected = 0;
+            }
+            Action::Back => return Transition::Pop,
+            _ => {}
+        }
+        Transition::None
+    }
+    fn handle_click(&mut self, _app: &mut App, item: usize) -> Transition {
+        self.selected = item;
+        Transition::None
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::widgets::achivements as tr;
+
+        let area = Block::bordered().inner(frame.size());
+        let [filters, table] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+
+        let filters_line: Line = vec![
+            format!(" {}: ", tr::difficulty()).into(),
+            self.difficulty
+                .map_or(tr::all().to_string(), |d| d.to_string())
+                .blue(),
+            format!("  {}: ", tr::mode()).into(),
+            self.mode
+                .map_or(tr::all().to_string(), |m| m.to_string())
+                .blue(),
+            format!("  {}: ", tr::sort()).into(),
+            self.sort.name().blue(),
+        ]
+        .into();
+        frame.render_widget(filters_line, filters);
+
+        // title, header and padding
+        self.page.set(table.height.saturating_sub(4) as usize);
+        let mut state = TableState::new()
+            .with_offset(self.offset.get())
+            .with_selected(Some(self.selected));
+        frame.render_stateful_widget(self.table(app), table, &mut state);
+        self.offset.set(state.offset());
+        for (i, area) in self.table(app).row_areas(table, state.offset()) {
+            app.clickable(area, Click::Item(i));
+        }
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::scroll(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::page(), [Action::PageUp, Action::PageDown]),
+            Keybind::new(tr::difficulty(), [Action::MoveLeft, Action::MoveRight]),
+            Keybind::new(tr::mode(), [Action::Filter]),
+            Keybind::new(tr::sort(), [Action::Sort]),
+            Keybind::new(tr::back(), [Action::Back]),
+        ]
+    }
+}
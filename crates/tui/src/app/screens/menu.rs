@@ -0,0 +1,121 @@
+use ratatui::Frame;
+
+use crate::{keys::Action, strings::tr, widgets};
+
+use super::{
+    cycle_index, App, BadgesScreen, Click, DifficultyScreen, ExchangeScreen, GameScreen, Keybind,
+    LeaderboardScreen, PlayersScreen, Screen, SettingsScreen, StatsScreen, Transition,
+};
+
+/// Title screen
+#[derive(Debug, Default)]
+pub(in crate::app) struct MenuScreen {
+    selected: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MenuItem {
+    NewGame,
+    Difficulty,
+    Leaderboard,
+    Stats,
+    Exchange,
+    Badges,
+    Player,
+    Settings,
+    Help,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [Self; 10] = [
+        Self::NewGame,
+        Self::Difficulty,
+        Self::Leaderboard,
+        Self::Stats,
+        Self::Exchange,
+        Self::Badges,
+        Self::Player,
+        Self::Settings,
+        Self::Help,
+        Self::Quit,
+    ];
+
+    fn name(self) -> &'static str {
+        use tr::widgets::menu as tr;
+
+        match self {
+            Self::NewGame => tr::new_game(),
+            Self::Difficulty => tr::difficulty(),
+            Self::Leaderboard => tr::leaderboard(),
+            Self::Stats => tr::stats(),
+            Self::Exchange => tr::exchange(),
+            Self::Badges => tr::badges(),
+            Self::Player => tr::player(),
+            Self::Settings => tr::settings(),
+            Self::Help => tr::help(),
+            Self::Quit => tr::quit(),
+        }
+    }
+}
+
+impl Screen for MenuScreen {
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let len = MenuItem::ALL.len();
+        match action {
+            Action::MoveUp | Action::MoveDown => {
+                self.selected = cycle_index(self.selected, len, action == Action::MoveDown)
+            }
+            Action::Submit => {
+                return match MenuItem::ALL[self.selected] {
+                    MenuItem::NewGame => Transition::push(GameScreen),
+                    MenuItem::Difficulty => Transition::push(DifficultyScreen::default()),
+                    MenuItem::Leaderboard => Transition::push(LeaderboardScreen::default()),
+                    MenuItem::Stats => Transition::push(StatsScreen::default()),
+                    MenuItem::Exchange => Transition::push(ExchangeScreen::default()),
+                    MenuItem::Badges => Transition::push(BadgesScreen),
+                    MenuItem::Player => Transition::push(PlayersScreen::default()),
+                    MenuItem::Settings => Transition::push(SettingsScreen::default()),
+                    MenuItem::Help => {
+                        let keybinds = GameScreen::game_keybinds(app, true);
+                        Transition::push(app.help_screen(keybinds))
+                    }
+                    MenuItem::Quit => Transition::Quit,
+                }
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+    fn handle_click(&mut self, app: &mut App, item: usize) -> Transition {
+        self.selected = item;
+        self.handle_action(app, Action::Submit)
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::widgets::app as tr;
+
+        // show current player
+        let items = MenuItem::ALL.map(|item| match item {
+            MenuItem::Player => format!("{}: {}", item.name(), app.user),
+            _ => item.name().to_string(),
+        });
+        let menu = widgets::Menu {
+            title: tr::title(),
+            items: items.iter().map(|i| i.as_str()).collect(),
+            selected: self.selected,
+        };
+        let area = app.center_area(frame.size());
+        for (i, item) in menu.item_areas(area).into_iter().enumerate() {
+            app.clickable(item, Click::Item(i));
+        }
+        frame.render_widget(menu, area);
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::submit(), [Action::Submit]),
+        ]
+    }
+}
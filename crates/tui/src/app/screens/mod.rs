@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    Frame,
+};
+
+use crate::keys::{Action, KeyContext};
+
+use super::App;
+
+mod badges;
+mod custom;
+mod difficulty;
+mod exchange;
+mod game;
+mod game_over;
+mod help;
+mod leaderboard;
+mod menu;
+mod new_player;
+mod pause;
+mod players;
+mod settings;
+mod stats;
+
+pub(super) use badges::BadgesScreen;
+pub(super) use custom::CustomScreen;
+pub(super) use difficulty::DifficultyScreen;
+pub(super) use exchange::ExchangeScreen;
+pub(super) use game::GameScreen;
+pub(super) use game_over::GameOverScreen;
+pub(super) use help::HelpScreen;
+pub(super) use leaderboard::LeaderboardScreen;
+pub(super) use menu::MenuScreen;
+pub(super) use new_player::NewPlayerScreen;
+pub(super) use pause::PauseScreen;
+pub(super) use players::PlayersScreen;
+pub(super) use settings::SettingsScreen;
+pub(super) use stats::StatsScreen;
+
+/// Single screen of application. Screens are kept in a stack, only the top one
+/// receives keys
+pub(super) trait Screen: std::fmt::Debug {
+    /// Called when screen is put on the stack
+    fn on_enter(&mut self, _app: &mut App) {}
+    /// Called when screen is removed from the stack
+    fn on_exit(&mut self, _app: &mut App) {}
+    /// Which keybindings are used
+    fn context(&self) -> KeyContext {
+        KeyContext::Menu
+    }
+    /// Handle key before it is converted to action, e.g. for text input.
+    /// Returns `None` if key should be handled as action
+    fn handle_key(&mut self, _app: &mut App, _event: KeyEvent) -> Option<Transition> {
+        None
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition;
+    /// Handle click on item, which was registered with [`Click::Item`] during
+    /// render
+    fn handle_click(&mut self, _app: &mut App, _item: usize) -> Transition {
+        Transition::None
+    }
+    /// Called when terminal loses focus while screen is on top
+    fn on_focus_lost(&mut self, _app: &mut App) -> Transition {
+        Transition::None
+    }
+    /// Called periodically while screen is on top
+    fn tick(&mut self, _app: &mut App) -> Transition {
+        Transition::None
+    }
+    /// How often [`Screen::tick`] is called and keys are handled
+    fn tick_duration(&self, app: &App) -> Duration {
+        app.ui_fps
+    }
+    /// Screen handles all keys as text in [`Screen::handle_key`], so global
+    /// keys do not work
+    fn takes_text(&self) -> bool {
+        false
+    }
+    /// Previous screen is drawn below this one
+    fn is_overlay(&self) -> bool {
+        false
+    }
+    fn render(&self, app: &App, frame: &mut Frame);
+    /// Keys shown in help bar
+    fn keybinds(&self, app: &App) -> Vec<Keybind>;
+}
+
+/// What to do with screens stack after handling key
+#[derive(Debug, Default)]
+pub(super) enum Transition {
+    #[default]
+    None,
+    Push(Box<dyn Screen>),
+    Pop,
+    /// Replace top screen
+    Replace(Box<dyn Screen>),
+    /// Remove all screens above main menu
+    Menu,
+    Quit,
+}
+
+impl Transition {
+    pub(super) fn push(screen: impl Screen + 'static) -> Self {
+        Self::Push(Box::new(screen))
+    }
+    pub(super) fn replace(screen: impl Screen + 'static) -> Self {
+        Self::Replace(Box::new(screen))
+    }
+}
+
+/// What happens on mouse click on some area of the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Click {
+    /// Same as pressing key of action
+    Action(Action),
+    /// Item of list on the top screen, e.g. menu entry
+    Item(usize),
+    /// Achivement from side panel, by index in all achivements
+    Achivement(usize),
+}
+
+/// Name of keybinding and actions, keys of which are shown together
+#[derive(Debug, Clone)]
+pub(super) struct Keybind {
+    pub name: &'static str,
+    pub actions: Vec<Action>,
+}
+
+impl Keybind {
+    pub(super) fn new(name: &'static str, actions: impl Into<Vec<Action>>) -> Self {
+        Self {
+            name,
+            actions: actions.into(),
+        }
+    }
+}
+
+/// Next or previous item after `current`, going through `None` between the
+/// last and the first items. Used for filters, where `None` means all items
+pub(super) fn cycle_filter<T: PartialEq + Clone>(
+    items: &[T],
+    current: &Option<T>,
+    forward: bool,
+) -> Option<T> {
+    let pos = current
+        .as_ref()
+        .and_then(|c| items.iter().position(|i| i == c));
+    let i = match (pos, forward) {
+        (None, true) => 0,
+        (None, false) => items.len().checked_sub(1)?,
+        (Some(i), true) => i + 1,
+        (Some(i), false) => i.checked_sub(1)?,
+    };
+    items.get(i).cloned()
+}
+
+/// Next or previous item after `current`, wrapping around. Unknown `current`
+/// is treated as the first item
+pub(super) fn cycle<T: PartialEq + Clone>(items: &[T], current: &T, forward: bool) -> T {
+    let i = items.iter().position(|i| i == current).unwrap_or_default();
+    items[cycle_index(i, items.len(), forward)].clone()
+}
+
+/// Next or previous index of list with `len` items, wrapping around
+pub(super) fn cycle_index(i: usize, len: usize, forward: bool) -> usize {
+    if forward {
+        (i + 1) % len
+    } else {
+        (i + len - 1) % len
+    }
+}
+
+/// Character typed into text field. Keys with Ctrl or Alt held are not text
+pub(super) fn typed_char(event: KeyEvent) -> Option<char> {
+    match event.code {
+        KeyCode::Char(c) if (event.modifiers - KeyModifiers::SHIFT).is_empty() => Some(c),
+        _ => None,
+    }
+}
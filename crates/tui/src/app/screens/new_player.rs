@@ -0,0 +1,92 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Alignment,
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    config::validate_player,
+    keys::{Action, KeyContext},
+    strings::tr,
+};
+
+use super::{typed_char, App, Keybind, Screen, Transition};
+
+/// Text entry for name of new player
+#[derive(Debug, Default)]
+pub(in crate::app) struct NewPlayerScreen {
+    name: String,
+    /// Why name can't be used
+    error: Option<String>,
+}
+
+impl NewPlayerScreen {
+    fn submit(&mut self, app: &mut App) -> Transition {
+        let name = self.name.trim();
+        if let Err(e) = validate_player(name) {
+            self.error = Some(e.to_string());
+            return Transition::None;
+        }
+        app.select_player(name);
+        Transition::Pop
+    }
+}
+
+impl Screen for NewPlayerScreen {
+    fn handle_key(&mut self, app: &mut App, event: KeyEvent) -> Option<Transition> {
+        if let Some(c) = typed_char(event) {
+            self.name.push(c);
+            return Some(Transition::None);
+        }
+        let t = match event.code {
+            KeyCode::Backspace => {
+                self.name.pop();
+                Transition::None
+            }
+            code => match app.keys.action(KeyContext::Menu, code) {
+                Some(Action::Submit) => self.submit(app),
+                Some(Action::Back) => Transition::Pop,
+                _ => Transition::None,
+            },
+        };
+        Some(t)
+    }
+    fn handle_action(&mut self, _app: &mut App, _action: Action) -> Transition {
+        // all keys are handled as text
+        Transition::None
+    }
+    fn takes_text(&self) -> bool {
+        true
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::widgets::players as tr;
+
+        let mut text: Vec<Line> = vec![
+            tr::new_player().bold().into(),
+            "".into(),
+            vec![
+                format!("{}: ", tr::name()).into(),
+                format!("{}_", self.name).blue(),
+            ]
+            .into(),
+        ];
+        if let Some(error) = &self.error {
+            text.extend(["".into(), error.clone().red().into()]);
+        }
+        let paragraph = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, app.center_area(frame.size()));
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::submit(), [Action::Submit]),
+            Keybind::new(tr::cancel(), [Action::Back]),
+        ]
+    }
+}
@@ -0,0 +1,104 @@
+use ratatui::{widgets::Clear, Frame};
+
+use crate::{keys::Action, strings::tr, widgets};
+
+use super::{cycle_index, App, Click, Keybind, Screen, SettingsScreen, Transition};
+
+/// Menu of paused game, shown over game field
+#[derive(Debug, Default)]
+pub(in crate::app) struct PauseScreen {
+    selected: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PauseItem {
+    Resume,
+    Restart,
+    Settings,
+    Menu,
+}
+
+impl PauseItem {
+    const ALL: [Self; 4] = [Self::Resume, Self::Restart, Self::Settings, Self::Menu];
+
+    fn name(self) -> &'static str {
+        use tr::widgets::pause as tr;
+
+        match self {
+            Self::Resume => tr::resume(),
+            Self::Restart => tr::restart(),
+            Self::Settings => tr::settings(),
+            Self::Menu => tr::menu(),
+        }
+    }
+}
+
+impl PauseScreen {
+    fn menu(&self) -> widgets::Menu<'static> {
+        widgets::Menu {
+            title: tr::widgets::pause::pause(),
+            items: PauseItem::ALL.map(PauseItem::name).to_vec(),
+            selected: self.selected,
+        }
+    }
+}
+
+impl Screen for PauseScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        app.pause();
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let len = PauseItem::ALL.len();
+        match action {
+            Action::MoveUp | Action::MoveDown => {
+                self.selected = cycle_index(self.selected, len, action == Action::MoveDown)
+            }
+            Action::Back => {
+                app.resume();
+                return Transition::Pop;
+            }
+            Action::Submit => {
+                return match PauseItem::ALL[self.selected] {
+                    PauseItem::Resume => {
+                        app.resume();
+                        Transition::Pop
+                    }
+                    PauseItem::Restart => {
+                        app.restart();
+                        app.resume();
+                        Transition::Pop
+                    }
+                    PauseItem::Settings => Transition::push(SettingsScreen::default()),
+                    PauseItem::Menu => Transition::Menu,
+                }
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+    fn handle_click(&mut self, app: &mut App, item: usize) -> Transition {
+        self.selected = item;
+        self.handle_action(app, Action::Submit)
+    }
+    fn is_overlay(&self) -> bool {
+        true
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        let area = app.center_area(frame.size());
+        frame.render_widget(Clear, area);
+        let menu = self.menu();
+        for (i, item) in menu.item_areas(area).into_iter().enumerate() {
+            app.clickable(item, Click::Item(i));
+        }
+        frame.render_widget(menu, area);
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::submit(), [Action::Submit]),
+            Keybind::new(tr::resume(), [Action::Back]),
+        ]
+    }
+}
@@ -0,0 +1,66 @@
+use ratatui::Frame;
+
+use crate::{keys::Action, strings::tr, widgets};
+
+use super::{cycle_index, App, Click, Keybind, NewPlayerScreen, Screen, Transition};
+
+/// List of players to switch between
+#[derive(Debug, Default)]
+pub(in crate::app) struct PlayersScreen {
+    selected: usize,
+}
+
+impl Screen for PlayersScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        let players = &app.config.players;
+        self.selected = players.iter().position(|p| *p == app.user).unwrap_or(0);
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        // last item is for new player
+        let len = app.config.players.len() + 1;
+        match action {
+            Action::MoveUp | Action::MoveDown => {
+                self.selected = cycle_index(self.selected, len, action == Action::MoveDown)
+            }
+            Action::Submit => {
+                let Some(name) = app.config.players.get(self.selected).cloned() else {
+                    return Transition::replace(NewPlayerScreen::default());
+                };
+                app.select_player(&name);
+                return Transition::Pop;
+            }
+            Action::Back => return Transition::Pop,
+            _ => {}
+        }
+        Transition::None
+    }
+    fn handle_click(&mut self, app: &mut App, item: usize) -> Transition {
+        self.selected = item;
+        self.handle_action(app, Action::Submit)
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::widgets::players as tr;
+
+        let mut items: Vec<_> = app.config.players.iter().map(|p| p.as_str()).collect();
+        items.push(tr::new_player());
+        let menu = widgets::Menu {
+            title: tr::title(),
+            items,
+            selected: self.selected,
+        };
+        let area = app.center_area(frame.size());
+        for (i, item) in menu.item_areas(area).into_iter().enumerate() {
+            app.clickable(item, Click::Item(i));
+        }
+        frame.render_widget(menu, area);
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::submit(), [Action::Submit]),
+            Keybind::new(tr::back(), [Action::Back]),
+        ]
+    }
+}
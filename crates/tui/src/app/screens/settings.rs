@@ -0,0 +1,286 @@
+use std::time::Duration;
+
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Padding, Paragraph, Wrap},
+    Frame,
+};
+
+use lib::{Game, GameOptions, MoveTo, Pos};
+
+use crate::{
+    app::fps,
+    config::{system_player, Config, Renderer},
+    keys::{Action, ControlScheme, Preset},
+    sound::SoundEvent,
+    strings::{self, tr},
+    theme::{ColorMode, Theme},
+};
+
+use super::{cycle, cycle_index, App, Keybind, Screen, Transition};
+
+const PREVIEW_SIZE: Pos = Pos::new(12, 6);
+const PREVIEW_FPS: Duration = fps(10);
+const FPS_STEPS: [u64; 7] = [10, 15, 20, 30, 60, 120, 144];
+
+/// Settings editor. Changes are applied immediately and saved on submit
+#[derive(Debug, Default)]
+pub(in crate::app) struct SettingsScreen {
+    selected: usize,
+    /// Edited settings, can be invalid
+    config: Config,
+    /// Settings before changes, restored on cancel
+    original: Config,
+    original_user: String,
+    /// Why edited settings can't be applied
+    error: Option<String>,
+    /// Small field with snake, shows how theme and renderer look
+    preview: Game,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    Theme,
+    Renderer,
+    Mode,
+    Keys,
+    Controls,
+    UiFps,
+    Sound,
+    Player,
+    Language,
+    Mouse,
+}
+
+impl Setting {
+    const ALL: [Self; 10] = [
+        Self::Theme,
+        Self::Renderer,
+        Self::Mode,
+        Self::Keys,
+        Self::Controls,
+        Self::UiFps,
+        Self::Sound,
+        Self::Player,
+        Self::Language,
+        Self::Mouse,
+    ];
+
+    fn name(self) -> &'static str {
+        use tr::widgets::settings as tr;
+
+        match self {
+            Self::Theme => tr::theme(),
+            Self::Renderer => tr::renderer(),
+            Self::Mode => tr::mode(),
+            Self::Keys => tr::keys(),
+            Self::Controls => tr::controls(),
+            Self::UiFps => tr::ui_fps(),
+            Self::Sound => tr::sound(),
+            Self::Player => tr::player(),
+            Self::Language => tr::language(),
+            Self::Mouse => tr::mouse(),
+        }
+    }
+    fn value(self, config: &Config) -> String {
+        use tr::widgets::settings as tr;
+
+        let ui = &config.ui;
+        match self {
+            Self::Theme => ui.theme.clone(),
+            Self::Renderer => format!("{:?}", ui.renderer).to_lowercase(),
+            Self::Mode => format!("{:?}", ui.mode).to_lowercase(),
+            Self::Keys => format!("{:?}", config.keys.preset).to_lowercase(),
+            Self::Controls => format!("{:?}", config.keys.scheme).to_lowercase(),
+            Self::UiFps => ui.fps.to_string(),
+            Self::Sound if config.sound.enabled => tr::on().to_string(),
+            Self::Sound => tr::off().to_string(),
+            Self::Mouse if ui.mouse => tr::on().to_string(),
+            Self::Mouse => tr::off().to_string(),
+            Self::Player => config
+                .user
+                .clone()
+                .unwrap_or_else(|| tr::system_user().to_string()),
+            Self::Language => ui
+                .language
+                .clone()
+                .unwrap_or_else(|| tr::system_language().to_string()),
+        }
+    }
+    /// Select next or previous value
+    fn change(self, config: &mut Config, forward: bool) {
+        let ui = &mut config.ui;
+        match self {
+            Self::Theme => {
+                let names = Theme::names(&config.themes);
+                ui.theme = cycle(&names, &ui.theme, forward);
+            }
+            Self::Renderer => ui.renderer = cycle(&Renderer::ALL, &ui.renderer, forward),
+            Self::Mode => ui.mode = cycle(&ColorMode::ALL, &ui.mode, forward),
+            Self::Keys => {
+                let keys = &mut config.keys;
+                keys.preset = cycle(&Preset::ALL, &keys.preset, forward);
+            }
+            Self::Controls => {
+                let keys = &mut config.keys;
+                keys.scheme = cycle(&ControlScheme::ALL, &keys.scheme, forward);
+            }
+            Self::UiFps => {
+                let step = if forward {
+                    FPS_STEPS.iter().find(|&&f| f > ui.fps)
+                } else {
+                    FPS_STEPS.iter().rev().find(|&&f| f < ui.fps)
+                };
+                ui.fps = step.copied().unwrap_or(ui.fps);
+            }
+            Self::Sound => config.sound.enabled = !config.sound.enabled,
+            Self::Mouse => ui.mouse = !ui.mouse,
+            Self::Player if config.players.is_empty() => {}
+            Self::Player => {
+                let current = config.user.clone().unwrap_or_else(system_player);
+                config.user = Some(cycle(&config.players, &current, forward));
+            }
+            Self::Language => {
+                let languages: Vec<_> = [None]
+                    .into_iter()
+                    .chain(strings::languages().into_iter().map(Some))
+                    .collect();
+                ui.language = cycle(&languages, &ui.language, forward);
+            }
+        }
+    }
+}
+
+impl SettingsScreen {
+    fn setting(&self) -> Setting {
+        Setting::ALL[self.selected]
+    }
+    /// Apply edited settings to app, so changes are visible immediately
+    fn preview(&mut self, app: &mut App) {
+        let ring = self.config.sound.enabled && !app.config.sound.enabled;
+        let config = Config {
+            // could be changed while screen is open
+            difficulty: app.config.difficulty,
+            show_achivements_grouped: app.config.show_achivements_grouped,
+            debug: app.config.debug,
+            ..self.config.clone()
+        };
+        self.error = app.apply_config(config).err().map(|e| e.to_string());
+        if ring {
+            app.play_sound(SoundEvent::Food);
+        }
+    }
+    fn restart_preview(&mut self) {
+        let options = GameOptions {
+            seed: Some(0),
+            ..Default::default()
+        };
+        if let Ok(game) = Game::with_options(PREVIEW_SIZE, options) {
+            self.preview = game;
+        }
+    }
+    /// Move preview snake towards food
+    fn steer_preview(&self) {
+        let game = &self.preview;
+        let ((hx, hy), (fx, fy)) = (game.head().into(), game.food().into());
+        let to = match (hx.cmp(&fx), hy.cmp(&fy)) {
+            (std::cmp::Ordering::Less, _) => MoveTo::Right,
+            (std::cmp::Ordering::Greater, _) => MoveTo::Left,
+            (_, std::cmp::Ordering::Less) => MoveTo::Down,
+            _ => MoveTo::Up,
+        };
+        game.rotate_to(to);
+    }
+}
+
+impl Screen for SettingsScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        self.config = app.config.clone();
+        self.original = app.config.clone();
+        self.original_user = app.user.clone();
+        self.restart_preview();
+    }
+    fn handle_action(&mut self, app: &mut App, action: Action) -> Transition {
+        let len = Setting::ALL.len();
+        match action {
+            Action::MoveUp | Action::MoveDown => {
+                self.selected = cycle_index(self.selected, len, action == Action::MoveDown)
+            }
+            Action::MoveLeft | Action::MoveRight => {
+                self.setting()
+                    .change(&mut self.config, action == Action::MoveRight);
+                self.preview(app);
+            }
+            Action::Submit if self.error.is_none() => {
+                app.save_settings();
+                return Transition::Pop;
+            }
+            Action::Back => {
+                self.config = self.original.clone();
+                self.preview(app);
+                app.user = self.original_user.clone();
+                return Transition::Pop;
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+    fn tick(&mut self, _app: &mut App) -> Transition {
+        if self.preview.stats().status != lib::GameStatus::Play {
+            self.restart_preview();
+        }
+        self.steer_preview();
+        self.preview.move_snake();
+        Transition::None
+    }
+    fn tick_duration(&self, _app: &App) -> Duration {
+        PREVIEW_FPS
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        use tr::widgets::settings as tr;
+
+        let area = Block::bordered().inner(frame.size());
+        let [list, preview] = Layout::horizontal([Constraint::Fill(1); 2]).areas(area);
+
+        let mut text: Vec<Line> = vec![tr::title().bold().into(), "".into()];
+        text.extend(Setting::ALL.iter().enumerate().map(|(i, &setting)| {
+            let name = format!("{}: ", setting.name());
+            if i != self.selected {
+                return vec![name.into(), setting.value(&self.config).blue()].into();
+            }
+            let value = format!("< {} >", setting.value(&self.config));
+            vec![name.bold(), value.blue().bold()].into()
+        }));
+        if let Some(error) = &self.error {
+            text.extend(["".into(), error.clone().red().into()]);
+        }
+        let paragraph = Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(Block::new().padding(Padding::uniform(1)));
+        frame.render_widget(paragraph, list);
+
+        let (width, height) = app.field_area_size(PREVIEW_SIZE);
+        let [preview] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(preview);
+        let [preview] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(preview);
+        let preview = preview.intersection(area);
+        if preview != Rect::default() {
+            app.render_field(frame, preview, &self.preview);
+        }
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::change(), [Action::MoveLeft, Action::MoveRight]),
+            Keybind::new(tr::save(), [Action::Submit]),
+            Keybind::new(tr::cancel(), [Action::Back]),
+        ]
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use chrono::{Local, NaiveDate, TimeDelta};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Style, Stylize},
+    symbols::Marker,
+    text::Line,
+    widgets::{
+        Axis, Bar, BarChart, BarGroup, Block, Chart, Dataset, GraphType, Paragraph, Sparkline,
+    },
+    Frame,
+};
+
+use lib::DeathCause;
+
+use crate::{
+    difficulty::DifficultyKind,
+    history::{self, Run},
+    keys::Action,
+    strings::{self, tr},
+};
+
+use super::{cycle_filter, App, Keybind, Screen, Transition};
+
+/// Number of bars in score histogram of each difficulty
+const SCORE_BINS: usize = 5;
+
+/// Charts of finished games
+#[derive(Debug, Default)]
+pub(in crate::app) struct StatsScreen {
+    runs: Vec<Run>,
+    /// Selected filter, 0 for user, 1 for difficulty
+    selected: usize,
+    /// Show only runs of this player
+    user: Option<String>,
+    /// Show only runs on this difficulty
+    difficulty: Option<DifficultyKind>,
+}
+
+impl StatsScreen {
+    fn filtered(&self) -> Vec<&Run> {
+        self.runs
+            .iter()
+            .filter(|r| {
+                self.user.as_ref().is_none_or(|u| *u == r.username)
+                    && self.difficulty.is_none_or(|d| d == r.difficulty)
+            })
+            .collect()
+    }
+    /// Select next or previous value of selected filter. `None` means all
+    fn change_filter(&mut self, forward: bool) {
+        if self.selected == 0 {
+            let mut users: Vec<_> = self.runs.iter().map(|r| r.username.clone()).collect();
+            users.sort();
+            users.dedup();
+            self.user = cycle_filter(&users, &self.user, forward);
+        } else {
+            let mut difficulties: Vec<_> = self.runs.iter().map(|r| r.difficulty).collect();
+            difficulties.sort();
+            difficulties.dedup();
+            self.difficulty = cycle_filter(&difficulties, &self.difficulty, forward);
+        }
+    }
+    fn render_filters(&self, frame: &mut Frame, area: Rect) {
+        use tr::widgets::stats as tr;
+
+        let filters = [
+            (tr::player(), self.user.clone()),
+            (tr::difficulty(), self.difficulty.map(|d| d.to_string())),
+        ];
+        let mut line: Vec<_> = vec![tr::title().bold(), "  ".into()];
+        for (i, (name, value)) in filters.into_iter().enumerate() {
+            let value = value.unwrap_or_else(|| tr::all().to_string());
+            line.push(format!("  {name}: ").into());
+            if i == self.selected {
+                line.push(format!("< {value} >").blue().bold());
+            } else {
+                line.push(value.blue());
+            }
+        }
+        frame.render_widget(Line::from(line), area);
+    }
+    fn render_scores(&self, frame: &mut Frame, area: Rect, runs: &[&Run]) {
+        let (Some(first), Some(last)) = (runs.first(), runs.last()) else {
+            return;
+        };
+        let points: Vec<_> = runs
+            .iter()
+            .map(|r| (r.date.timestamp() as f64, r.score as f64))
+            .collect();
+        let max = runs.iter().map(|r| r.score).max().unwrap_or_default();
+        let (start, end) = (first.date.timestamp() as f64, last.date.timestamp() as f64);
+
+        let dataset = Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().blue())
+            .data(&points);
+        let chart = Chart::new(vec![dataset])
+            .block(Block::bordered().title(tr::widgets::stats::score_over_time()))
+            .x_axis(
+                Axis::default()
+                    .bounds([start, end.max(start + 1.0)])
+                    .labels(vec![
+                        first.date.format("%Y-%m-%d").to_string().into(),
+                        last.date.format("%Y-%m-%d").to_string().into(),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, max.max(1) as f64])
+                    .labels(vec!["0".into(), max.to_string().into()]),
+            );
+        frame.render_widget(chart, area);
+    }
+    fn render_histogram(&self, frame: &mut Frame, area: Rect, runs: &[&Run]) {
+        let max = runs.iter().map(|r| r.score).max().unwrap_or_default();
+        let bin = (max + 1).div_ceil(SCORE_BINS).max(1);
+
+        let mut counts: BTreeMap<DifficultyKind, [u64; SCORE_BINS]> = BTreeMap::new();
+        for r in runs {
+            counts.entry(r.difficulty).or_default()[(r.score / bin).min(SCORE_BINS - 1)] += 1;
+        }
+
+        let title = strings::format(tr::widgets::stats::scores(), &[("size", bin.to_string())]);
+        let mut chart = BarChart::default()
+            .block(Block::bordered().title(title))
+            .bar_width(1)
+            .bar_gap(0)
+            .group_gap(2)
+            .bar_style(Style::new().green());
+        for (difficulty, counts) in counts {
+            // values don't fit into narrow bars
+            let bars: Vec<_> = counts
+                .iter()
+                .map(|&c| Bar::default().value(c).text_value(String::new()))
+                .collect();
+            // label should fit under the group
+            let label = difficulty
+                .to_string()
+                .chars()
+                .take(SCORE_BINS)
+                .collect::<String>();
+            chart = chart.data(BarGroup::default().label(label.into()).bars(&bars));
+        }
+        frame.render_widget(chart, area);
+    }
+    fn render_per_day(&self, frame: &mut Frame, area: Rect, runs: &[&Run]) {
+        let days = area.width.saturating_sub(2) as i64;
+        let today = Local::now().date_naive();
+        let first = today - TimeDelta::days(days - 1);
+
+        let mut per_day: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+        for r in runs {
+            *per_day.entry(r.date.date_naive()).or_default() += 1;
+        }
+        let data: Vec<_> = first
+            .iter_days()
+            .take(days.max(0) as usize)
+            .map(|d| per_day.get(&d).copied().unwrap_or_default())
+            .collect();
+        let best = history::best_per_day(runs.iter().copied())
+            .get(&today)
+            .map(|r| r.score)
+            .unwrap_or_default();
+
+        let title = strings::format(
+            tr::widgets::stats::games_per_day(),
+            &[("score", best.to_string())],
+        );
+        let sparkline = Sparkline::default()
+            .block(Block::bordered().title(title))
+            .style(Style::new().yellow())
+            .data(&data);
+        frame.render_widget(sparkline, area);
+    }
+    fn render_deaths(&self, frame: &mut Frame, area: Rect, runs: &[&Run]) {
+        use tr::widgets::stats as tr;
+
+        let mut causes: BTreeMap<&str, u64> = BTreeMap::new();
+        for r in runs {
+            let cause = match r.death {
+                Some(DeathCause::SelfCollision) => tr::self_collision(),
+                Some(DeathCause::Wall) => tr::wall(),
+                None => tr::won(),
+            };
+            *causes.entry(cause).or_default() += 1;
+        }
+
+        let bars: Vec<_> = causes
+            .into_iter()
+            .map(|(cause, count)| Bar::default().label(cause.into()).value(count))
+            .collect();
+        let chart = BarChart::default()
+            .block(Block::bordered().title(tr::deaths()))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_style(Style::new().red())
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, area);
+    }
+}
+
+impl Screen for StatsScreen {
+    fn on_enter(&mut self, app: &mut App) {
+        match history::read() {
+            Ok(runs) => self.runs = runs,
+            Err(e) => app.error = Some(Err(e)),
+        }
+        self.user = Some(app.user.clone()).filter(|u| self.runs.iter().any(|r| r.username == *u));
+    }
+    fn handle_action(&mut self, _app: &mut App, action: Action) -> Transition {
+        match action {
+            Action::MoveUp | Action::MoveDown => self.selected = 1 - self.selected,
+            Action::MoveLeft | Action::MoveRight => self.change_filter(action == Action::MoveRight),
+            Action::Back => return Transition::Pop,
+            _ => {}
+        }
+        Transition::None
+    }
+    fn render(&self, app: &App, frame: &mut Frame) {
+        let area = Block::bordered().inner(frame.size());
+        let [filters, charts] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        self.render_filters(frame, filters);
+
+        let runs = self.filtered();
+        if runs.is_empty() {
+            let text = Paragraph::new(tr::widgets::stats::no_games()).centered();
+            frame.render_widget(text, app.center_area(charts));
+            return;
+        }
+
+        let [top, bottom] = Layout::vertical([Constraint::Fill(1); 2]).areas(charts);
+        let [scores, histogram] = Layout::horizontal([Constraint::Fill(1); 2]).areas(top);
+        let [per_day, deaths] = Layout::horizontal([Constraint::Fill(1); 2]).areas(bottom);
+        self.render_scores(frame, scores, &runs);
+        self.render_histogram(frame, histogram, &runs);
+        self.render_per_day(frame, per_day, &runs);
+        self.render_deaths(frame, deaths, &runs);
+    }
+    fn keybinds(&self, _app: &App) -> Vec<Keybind> {
+        use tr::keybind as tr;
+
+        vec![
+            Keybind::new(tr::select(), [Action::MoveUp, Action::MoveDown]),
+            Keybind::new(tr::change(), [Action::MoveLeft, Action::MoveRight]),
+            Keybind::new(tr::back(), [Action::Back]),
+        ]
+    }
+}
@@ -0,0 +1,382 @@
+use std::{collections::VecDeque, path::PathBuf, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
+
+use lib::{Game, GameStatus, MoveTo};
+
+use crate::{csv, difficulty::DifficultyKind, paths::config_dif, storage, strings::tr};
+
+const FILE: &str = "badges.csv";
+const VERSION: u32 = 1;
+
+/// Food count and time for [`Badge::Hungry`]
+const HUNGRY_FOOD: usize = 10;
+const HUNGRY_TIME: Duration = Duration::from_secs(30);
+/// Moves without turning for [`Badge::Straight`]
+const STRAIGHT_TICKS: usize = 1000;
+
+/// Achievement unlocked by playing in some way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Badge {
+    /// Eat first food
+    FirstBite,
+    /// Eat [`HUNGRY_FOOD`] in [`HUNGRY_TIME`]
+    Hungry,
+    /// Fill half of the field
+    HalfBoard,
+    /// Win on hard or harder difficulty
+    HardWin,
+    /// Move [`STRAIGHT_TICKS`] without turning
+    Straight,
+}
+
+impl Badge {
+    pub const ALL: [Self; 5] = [
+        Self::FirstBite,
+        Self::Hungry,
+        Self::HalfBoard,
+        Self::HardWin,
+        Self::Straight,
+    ];
+
+    /// Name in file
+    fn id(self) -> &'static str {
+        match self {
+            Self::FirstBite => "first_bite",
+            Self::Hungry => "hungry",
+            Self::HalfBoard => "half_board",
+            Self::HardWin => "hard_win",
+            Self::Straight => "straight",
+        }
+    }
+    pub fn name(self) -> &'static str {
+        use tr::badges as tr;
+
+        match self {
+            Self::FirstBite => tr::first_bite(),
+            Self::Hungry => tr::hungry(),
+            Self::HalfBoard => tr::half_board(),
+            Self::HardWin => tr::hard_win(),
+            Self::Straight => tr::straight(),
+        }
+    }
+    pub fn description(self) -> &'static str {
+        use tr::badges as tr;
+
+        match self {
+            Self::FirstBite => tr::first_bite_desc(),
+            Self::Hungry => tr::hungry_desc(),
+            Self::HalfBoard => tr::half_board_desc(),
+            Self::HardWin => tr::hard_win_desc(),
+            Self::Straight => tr::straight_desc(),
+        }
+    }
+}
+
+impl FromStr for Badge {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|b| b.id() == s)
+            .ok_or_else(|| anyhow!("unknown badge \"{s}\""))
+    }
+}
+
+/// Badge unlocked by user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unlock {
+    pub username: String,
+    pub badge: Badge,
+    pub date: DateTime<Local>,
+}
+
+/// Watches single game and checks conditions of badges
+#[derive(Debug, Default)]
+pub struct BadgeTracker {
+    /// Ticks when food was eaten, only for last [`HUNGRY_TIME`]
+    food_ticks: VecDeque<usize>,
+    score: usize,
+    direction: Option<MoveTo>,
+    /// Tick of last turn
+    turned_at: usize,
+}
+
+impl BadgeTracker {
+    /// Check game after snake moved. Returns badges with met conditions
+    pub fn update(
+        &mut self,
+        game: &Game,
+        difficulty: DifficultyKind,
+        tick: Duration,
+    ) -> Vec<Badge> {
+        let stats = game.stats();
+        let mut res = vec![];
+
+        if stats.score > self.score {
+            self.score = stats.score;
+            self.food_ticks.push_back(stats.ticks);
+            res.push(Badge::FirstBite);
+        }
+        // ticks for HUNGRY_TIME
+        let window = (HUNGRY_TIME.as_micros() / tick.as_micros().max(1)) as usize;
+        while self
+            .food_ticks
+            .front()
+            .is_some_and(|&t| t + window < stats.ticks)
+        {
+            self.food_ticks.pop_front();
+        }
+        if self.food_ticks.len() >= HUNGRY_FOOD {
+            res.push(Badge::Hungry);
+        }
+
+        let (x, y) = game.size().into();
+        if stats.score > 0 && game.snake().len() * 2 >= x * y {
+            res.push(Badge::HalfBoard);
+        }
+
+        // custom difficulty can be slow
+        let hard = difficulty >= DifficultyKind::Hard && difficulty != DifficultyKind::Custom;
+        if stats.status == GameStatus::Win && hard {
+            res.push(Badge::HardWin);
+        }
+
+        let direction = game.direction();
+        if self.direction != Some(direction) {
+            self.direction = Some(direction);
+            self.turned_at = stats.ticks;
+        }
+        if stats.ticks - self.turned_at >= STRAIGHT_TICKS {
+            res.push(Badge::Straight);
+        }
+
+        res
+    }
+}
+
+pub fn read_unlocks() -> Result<Vec<Unlock>> {
+    let _lock = storage::lock(&badges_file())?;
+    read_file()
+}
+
+/// Save new unlocks, keeping ones saved by other instances. Returns all
+/// unlocks
+pub fn add_unlocks(new: &[Unlock]) -> Result<Vec<Unlock>> {
+    let _lock = storage::lock(&badges_file())?;
+    let mut unlocks = read_file()?;
+    for u in new {
+        if !unlocks
+            .iter()
+            .any(|s| s.username == u.username && s.badge == u.badge)
+        {
+            unlocks.push(u.clone());
+        }
+    }
+    save_unlocks(&unlocks)?;
+    Ok(unlocks)
+}
+
+fn read_file() -> Result<Vec<Unlock>> {
+    let (_, unlocks) = storage::read(&badges_file(), VERSION, |_, fields| {
+        let [username, badge, date] = fields else {
+            return Err(anyhow!("unexpected elements count in entry"));
+        };
+        Ok(Unlock {
+            username: username.to_owned(),
+            badge: badge.parse()?,
+            date: DateTime::parse_from_rfc3339(date)
+                .context("invalid date")?
+                .with_timezone(&Local),
+        })
+    })?;
+    Ok(unlocks)
+}
+
+fn save_unlocks(unlocks: &[Unlock]) -> Result<()> {
+    let mut res = ["username", "badge", "date"].join(csv::SEP) + "\n";
+    for u in unlocks {
+        let line = [
+            csv::field(&u.username),
+            u.badge.id().to_string(),
+            u.date.to_rfc3339_opts(SecondsFormat::Secs, false),
+        ];
+        res += &(line.join(csv::SEP) + "\n");
+    }
+
+    storage::write(&badges_file(), VERSION, &res)
+}
+
+fn badges_file() -> PathBuf {
+    config_dif().join(FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use lib::{CoordType, GameOptions, Pos};
+
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(100);
+
+    /// Field in one row, so moving right reaches every food
+    fn game(width: CoordType, options: GameOptions) -> Game {
+        let options = GameOptions {
+            seed: Some(0),
+            ..options
+        };
+        Game::with_options(Pos::new(width, 1), options).unwrap()
+    }
+    /// Game where snake does not grow
+    fn short_game(width: CoordType) -> Game {
+        game(
+            width,
+            GameOptions {
+                growth: 0,
+                ..Default::default()
+            },
+        )
+    }
+
+    struct Tracked {
+        game: Game,
+        tracker: BadgeTracker,
+        difficulty: DifficultyKind,
+        tick: Duration,
+    }
+
+    impl Tracked {
+        fn new(game: Game) -> Self {
+            Self {
+                game,
+                tracker: BadgeTracker::default(),
+                difficulty: DifficultyKind::Normal,
+                tick: TICK,
+            }
+        }
+        /// Move once, returns met conditions
+        fn step(&mut self) -> Vec<Badge> {
+            self.game.move_snake();
+            self.tracker.update(&self.game, self.difficulty, self.tick)
+        }
+        /// Move until food is eaten, returns conditions met on eating
+        fn eat(&mut self) -> Vec<Badge> {
+            let score = self.game.stats().score;
+            loop {
+                let badges = self.step();
+                if self.game.stats().score > score {
+                    return badges;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn first_bite() {
+        let mut t = Tracked::new(short_game(20));
+        let mut moved = vec![];
+        while t.game.stats().score == 0 {
+            moved = t.step();
+        }
+        assert!(moved.contains(&Badge::FirstBite));
+        assert!(!t.step().contains(&Badge::FirstBite));
+    }
+
+    #[test]
+    fn hungry_in_time() {
+        let mut t = Tracked::new(short_game(20));
+        for _ in 1..HUNGRY_FOOD {
+            assert!(!t.eat().contains(&Badge::Hungry));
+        }
+        // at most 19 moves for every food, and window is 300 moves
+        assert!(t.eat().contains(&Badge::Hungry));
+    }
+
+    #[test]
+    fn hungry_too_slow() {
+        let mut t = Tracked::new(short_game(20));
+        // window is 3 moves
+        t.tick = Duration::from_secs(10);
+        for _ in 0..HUNGRY_FOOD * 2 {
+            assert!(!t.eat().contains(&Badge::Hungry));
+        }
+    }
+
+    #[test]
+    fn half_board() {
+        let mut t = Tracked::new(game(8, GameOptions::default()));
+        // snake is 2 and 3 cells of 8
+        assert!(!t.eat().contains(&Badge::HalfBoard));
+        assert!(!t.eat().contains(&Badge::HalfBoard));
+        assert!(t.eat().contains(&Badge::HalfBoard));
+    }
+
+    #[test]
+    fn half_board_needs_food() {
+        let options = GameOptions {
+            initial_length: 3,
+            ..Default::default()
+        };
+        let mut t = Tracked::new(game(4, options));
+        let badges = t.tracker.update(&t.game, t.difficulty, t.tick);
+        assert!(!badges.contains(&Badge::HalfBoard));
+    }
+
+    /// Badges for winning on `difficulty`, on field of two cells
+    fn win(difficulty: DifficultyKind) -> Vec<Badge> {
+        let mut t = Tracked::new(game(2, GameOptions::default()));
+        t.difficulty = difficulty;
+        let badges = t.eat();
+        assert_eq!(t.game.stats().status, GameStatus::Win);
+        badges
+    }
+
+    #[test]
+    fn hard_win() {
+        assert!(win(DifficultyKind::Hard).contains(&Badge::HardWin));
+        assert!(win(DifficultyKind::Impossible).contains(&Badge::HardWin));
+    }
+
+    #[test]
+    fn hard_win_needs_hard_difficulty() {
+        assert!(!win(DifficultyKind::Normal).contains(&Badge::HardWin));
+        // custom is placed after hard, but can be slow
+        assert!(!win(DifficultyKind::Custom).contains(&Badge::HardWin));
+    }
+
+    #[test]
+    fn straight() {
+        let mut t = Tracked::new(short_game(20));
+        // direction is remembered on the first move
+        for _ in 0..STRAIGHT_TICKS {
+            assert!(!t.step().contains(&Badge::Straight));
+        }
+        assert!(t.step().contains(&Badge::Straight));
+    }
+
+    #[test]
+    fn straight_is_reset_by_turn() {
+        let game = Game::with_options(
+            Pos::new(20, 2),
+            GameOptions {
+                seed: Some(0),
+                growth: 0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut t = Tracked::new(game);
+        for i in 0..STRAIGHT_TICKS * 3 {
+            if i % (STRAIGHT_TICKS / 2) == 0 {
+                let to = match t.game.direction() {
+                    MoveTo::Right => MoveTo::Down,
+                    _ => MoveTo::Right,
+                };
+                t.game.rotate_to(to);
+            }
+            assert!(!t.step().contains(&Badge::Straight));
+        }
+    }
+}
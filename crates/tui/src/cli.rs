@@ -0,0 +1,257 @@
+use std::{io::Write, path::PathBuf, str::FromStr};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+use lib::{CoordType, Pos};
+
+use crate::{
+    achive::{
+        export_achivements, import_achivements, read_achivements, reset_achivements, ExportFormat,
+    },
+    app::Options,
+    difficulty::{DifficultyKind, GameMode},
+    history::{self, Run},
+};
+
+/// Snake in the terminal
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Start with this difficulty. `custom` uses preset selected in config
+    #[arg(short, long)]
+    difficulty: Option<DifficultyKind>,
+    /// Use fixed field size instead of fitting it into terminal
+    #[arg(short, long, value_name = "WxH")]
+    size: Option<FieldSize>,
+    /// Seed for food placement
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Record scores under this name instead of last selected player
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Do not record scores
+    #[arg(long)]
+    no_save: bool,
+    /// Start game without showing main menu
+    #[arg(long)]
+    play: bool,
+    /// Read and save settings in this file. Scores and other data are still
+    /// kept in the default directory
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print leaderboard
+    Scores {
+        /// Show only this difficulty
+        #[arg(short, long)]
+        difficulty: Option<DifficultyKind>,
+        /// Remove all saved scores
+        #[arg(long, conflicts_with = "difficulty")]
+        reset: bool,
+    },
+    /// Export scores as CSV or JSON
+    Export {
+        /// Write to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// csv or json. Guessed from output file extension if not set
+        #[arg(short, long)]
+        format: Option<ExportFormat>,
+    },
+    /// Add scores exported on another machine, keeping the best ones
+    Import {
+        file: PathBuf,
+        /// csv or json. Guessed from file extension if not set
+        #[arg(short, long)]
+        format: Option<ExportFormat>,
+    },
+    /// Print finished games
+    History {
+        /// Number of latest games to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        last: usize,
+        /// Show only games of this player
+        #[arg(short, long)]
+        user: Option<String>,
+        /// Show only this difficulty
+        #[arg(short, long)]
+        difficulty: Option<DifficultyKind>,
+        /// Show best game of every day instead
+        #[arg(long, conflicts_with = "average")]
+        by_day: bool,
+        /// Show average score of every difficulty instead
+        #[arg(long)]
+        average: bool,
+    },
+}
+
+impl Cli {
+    pub fn config_file(&self) -> Option<PathBuf> {
+        self.config.clone()
+    }
+    pub fn options(&self) -> Options {
+        Options {
+            user: self.user.clone(),
+            difficulty: self.difficulty,
+            size: self.size.map(|s| s.0),
+            seed: self.seed,
+            no_save: self.no_save,
+            play: self.play,
+        }
+    }
+}
+
+impl Command {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Self::Scores { reset: true, .. } => reset_achivements(),
+            Self::Scores { difficulty, .. } => print_scores(difficulty),
+            Self::Export { output, format } => export_scores(output, format),
+            Self::Import { file, format } => {
+                let format = format.unwrap_or_else(|| ExportFormat::from_path(&file));
+                let changed = import_achivements(&file, format)?;
+                println!("{changed} scores added or improved");
+                Ok(())
+            }
+            Self::History {
+                last,
+                user,
+                difficulty,
+                by_day,
+                average,
+            } => {
+                let mut runs = history::read()?;
+                runs.retain(|r| {
+                    user.as_ref().is_none_or(|u| *u == r.username)
+                        && difficulty.is_none_or(|d| d == r.difficulty)
+                });
+                if by_day {
+                    print_best_per_day(&runs)
+                } else if average {
+                    print_averages(&runs)
+                } else {
+                    print_runs(history::last(&runs, last))
+                }
+            }
+        }
+    }
+}
+
+fn print_scores(difficulty: Option<DifficultyKind>) -> Result<()> {
+    let mut achivements = read_achivements()?;
+    achivements.retain(|a| difficulty.is_none_or(|d| d == a.difficulty));
+    achivements.sort_by(|a, b| {
+        (a.difficulty, a.mode, b.score, &a.username).cmp(&(
+            b.difficulty,
+            b.mode,
+            a.score,
+            &b.username,
+        ))
+    });
+
+    let width = achivements
+        .iter()
+        .map(|a| a.username.chars().count())
+        .max()
+        .unwrap_or_default();
+    let mut out = std::io::stdout().lock();
+    let mut current = None;
+    let mut place = 0;
+    for a in &achivements {
+        // scores of custom difficulty are ranked separately for every rules
+        if current != Some((a.difficulty, a.mode)) {
+            if current.is_some() {
+                writeln!(out)?;
+            }
+            match a.mode {
+                GameMode::Classic => writeln!(out, "{}", a.difficulty)?,
+                mode => writeln!(out, "{} {mode}", a.difficulty)?,
+            }
+            current = Some((a.difficulty, a.mode));
+            place = 0;
+        }
+        place += 1;
+        writeln!(out, "{place:>4}. {:<width$}  {}", a.username, a.score)?;
+    }
+    Ok(())
+}
+
+fn export_scores(output: Option<PathBuf>, format: Option<ExportFormat>) -> Result<()> {
+    let format = format
+        .or_else(|| output.as_deref().map(ExportFormat::from_path))
+        .unwrap_or_default();
+    let text = export_achivements(&read_achivements()?, format)?;
+    match output {
+        Some(path) => std::fs::write(&path, text)
+            .with_context(|| format!("failed to write {}", path.display())),
+        None => Ok(std::io::stdout().write_all(text.as_bytes())?),
+    }
+}
+
+fn print_runs<'a>(runs: impl Iterator<Item = &'a Run>) -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    for r in runs {
+        let (x, y) = r.size.into();
+        let secs = r.duration.as_secs();
+        writeln!(
+            out,
+            "{}  {:<8} {:<7} {:>5}  {:>2}:{:02}  {:>7}  {}  {}",
+            r.date.format("%Y-%m-%d %H:%M"),
+            r.difficulty,
+            r.mode,
+            r.score,
+            secs / 60,
+            secs % 60,
+            format!("{x}x{y}"),
+            r.seed,
+            r.username,
+        )?;
+    }
+    Ok(())
+}
+
+fn print_best_per_day(runs: &[Run]) -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    for (day, r) in history::best_per_day(runs) {
+        writeln!(
+            out,
+            "{day}  {:>5}  {:<8} {}",
+            r.score, r.difficulty, r.username
+        )?;
+    }
+    Ok(())
+}
+
+fn print_averages(runs: &[Run]) -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    for (difficulty, avg) in history::average_per_difficulty(runs) {
+        writeln!(out, "{difficulty:<8} {avg:>8.1}")?;
+    }
+    Ok(())
+}
+
+/// Field size in format `WxH`
+#[derive(Debug, Clone, Copy)]
+struct FieldSize(Pos);
+
+impl FromStr for FieldSize {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const ERR: &str = "size should be in format WxH, e.g. 20x10";
+        let (x, y) = s.split_once(['x', 'X']).ok_or(ERR)?;
+        let parse = |v: &str| v.trim().parse::<CoordType>().map_err(|_| ERR);
+        let (x, y) = (parse(x)?, parse(y)?);
+        if x == 0 || y == 0 {
+            return Err("size should not be empty");
+        }
+        Ok(Self(Pos::new(x, y)))
+    }
+}
@@ -0,0 +1,300 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use ratatui::symbols::Marker;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    difficulty::{CustomRules, DifficultyKind},
+    keys::{KeyBindings, KeysConfig},
+    paths::{config_dif, custom_config_file},
+    sound::{Cue, SoundEvent},
+    storage, strings,
+    theme::{ColorMode, Theme, DEFAULT_THEME},
+};
+
+const FILE: &str = "config.toml";
+/// Longest player name, so it fits in leaderboard
+const MAX_PLAYER_LEN: usize = 32;
+
+/// Settings saved between runs
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Last selected difficulty
+    pub difficulty: DifficultyKind,
+    /// Preset of rules used on custom difficulty
+    pub preset: Option<String>,
+    /// Named rules of custom difficulty
+    pub presets: BTreeMap<String, CustomRules>,
+    /// Show achivements on current difficulty instead of grouping by user
+    pub show_achivements_grouped: bool,
+    pub debug: bool,
+    /// Last selected player. System username is used if not set
+    pub user: Option<String>,
+    /// Names of players, scores are recorded under them
+    pub players: Vec<String>,
+    pub ui: UiConfig,
+    pub keys: KeysConfig,
+    pub sound: SoundConfig,
+    pub history: HistoryConfig,
+    /// User-defined themes
+    pub themes: BTreeMap<String, Theme>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// Maximum redraws per second
+    pub fps: u64,
+    /// Scale frame size to number of cells, for [`Renderer::Canvas`] and
+    /// [`Renderer::Cells`]
+    pub scale: [f64; 2],
+    pub renderer: Renderer,
+    /// Marker for drawing field with [`Renderer::Canvas`]
+    #[serde(with = "marker")]
+    pub marker: Marker,
+    /// Name of built-in or user-defined theme
+    pub theme: String,
+    pub mode: ColorMode,
+    /// Language of interface, e.g. `ru`. Taken from `LANG` if not set
+    pub language: Option<String>,
+    /// Capture mouse for clicking menus and scrolling lists. Terminal can't
+    /// select text while it is on
+    pub mouse: bool,
+}
+
+/// How game field is drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Renderer {
+    /// Every block is filled with color
+    #[default]
+    Canvas,
+    /// Blocks are drawn with symbols, showing snake's direction
+    Cells,
+    /// Two blocks in one symbol, so blocks are square
+    HalfBlock,
+}
+
+impl Renderer {
+    pub const ALL: [Self; 3] = [Self::Canvas, Self::Cells, Self::HalfBlock];
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoundConfig {
+    /// Play cues on game events
+    #[serde(alias = "bell")]
+    pub enabled: bool,
+    /// Cue of the same event is not repeated more often than this
+    pub min_interval_ms: u64,
+    pub food: Cue,
+    pub death: Cue,
+    pub win: Cue,
+    pub personal_best: Cue,
+}
+
+impl SoundConfig {
+    pub fn cue(&self, event: SoundEvent) -> &Cue {
+        match event {
+            SoundEvent::Food => &self.food,
+            SoundEvent::Death => &self.death,
+            SoundEvent::Win => &self.win,
+            SoundEvent::PersonalBest => &self.personal_best,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// Keep only this many latest runs, 0 to keep all
+    pub max_runs: usize,
+    /// Remove runs older than this many days, 0 to keep forever
+    pub max_days: u32,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_runs: 10000,
+            max_days: 0,
+        }
+    }
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_ms: 150,
+            food: Cue::Bell,
+            death: Cue::Bell,
+            win: Cue::Bell,
+            personal_best: Cue::Bell,
+        }
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            fps: 20,
+            scale: [4.1, 2.2],
+            renderer: Renderer::default(),
+            marker: Marker::Block,
+            theme: DEFAULT_THEME.to_string(),
+            mode: ColorMode::default(),
+            language: None,
+            mouse: false,
+        }
+    }
+}
+
+impl Config {
+    /// Read config from file. Returns default config if file does not exist
+    pub fn load() -> Result<Self> {
+        let file = config_file();
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+
+        let text = std::fs::read_to_string(&file).context("failed to read config")?;
+        let config: Self = toml::from_str(&text)
+            .with_context(|| format!("invalid config file {}", file.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("invalid config file {}", file.display()))?;
+        Ok(config)
+    }
+    pub fn save(&self) -> Result<()> {
+        let text = toml::to_string_pretty(self).context("failed to serialize config")?;
+        storage::replace(&config_file(), &text)
+    }
+    pub fn validate(&self) -> Result<()> {
+        const MAX_FPS: u64 = 1000;
+
+        let ui = &self.ui;
+        if !(1..=MAX_FPS).contains(&ui.fps) {
+            bail!("ui.fps should be from 1 to {MAX_FPS}, got {}", ui.fps);
+        }
+        if ui.scale.iter().any(|s| !s.is_finite() || *s < 1.0) {
+            bail!(
+                "ui.scale should contain numbers not less than 1, got {:?}",
+                ui.scale
+            );
+        }
+        for name in self.user.iter().chain(&self.players) {
+            validate_player(name)?;
+        }
+        for (name, rules) in &self.presets {
+            validate_preset(name)?;
+            rules
+                .validate()
+                .with_context(|| format!("invalid preset \"{name}\""))?;
+        }
+        if let Some(name) = &self.preset {
+            if !self.presets.contains_key(name) {
+                bail!("preset \"{name}\" is not in presets");
+            }
+        }
+        KeyBindings::new(&self.keys)?;
+        self.theme()?;
+        if let Some(lang) = &ui.language {
+            strings::validate_language(lang)?;
+        }
+        Ok(())
+    }
+    pub fn theme(&self) -> Result<Theme> {
+        Theme::find(&self.ui.theme, &self.themes)
+    }
+    /// Rules of selected preset, or default ones
+    pub fn custom_rules(&self) -> CustomRules {
+        self.preset
+            .as_ref()
+            .and_then(|p| self.presets.get(p))
+            .copied()
+            .unwrap_or_default()
+    }
+    /// Remember player, if not added yet
+    pub fn add_player(&mut self, name: &str) {
+        if !self.players.iter().any(|p| p == name) {
+            self.players.push(name.to_string());
+        }
+    }
+}
+
+/// Check that player's name can be shown and stored
+pub fn validate_player(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.trim() != name {
+        bail!("player name should not be empty or start or end with spaces, got \"{name}\"");
+    }
+    if name.chars().any(char::is_control) {
+        bail!("player name should not contain control characters, got {name:?}");
+    }
+    if name.chars().count() > MAX_PLAYER_LEN {
+        bail!("player name should not be longer than {MAX_PLAYER_LEN} characters, got \"{name}\"");
+    }
+    Ok(())
+}
+
+/// Player name from system login, changed to pass [`validate_player`]. Used
+/// when player is not selected
+pub fn system_player() -> String {
+    let name: String = whoami::username()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    let name: String = name.trim().chars().take(MAX_PLAYER_LEN).collect();
+    match name.trim_end() {
+        "" => "player".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Check that name of custom difficulty preset can be shown and stored
+pub fn validate_preset(name: &str) -> Result<()> {
+    const MAX_LEN: usize = 24;
+
+    if name.trim().is_empty() || name.trim() != name {
+        bail!("preset name should not be empty or start or end with spaces, got \"{name}\"");
+    }
+    if name.chars().any(char::is_control) {
+        bail!("preset name should not contain control characters, got {name:?}");
+    }
+    if name.chars().count() > MAX_LEN {
+        bail!("preset name should not be longer than {MAX_LEN} characters, got \"{name}\"");
+    }
+    Ok(())
+}
+
+fn config_file() -> PathBuf {
+    custom_config_file().unwrap_or_else(|| config_dif().join(FILE))
+}
+
+/// (De)serialize [`Marker`] by its name
+mod marker {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(marker: &Marker, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&marker.to_string().to_lowercase())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Marker, D::Error> {
+        let s = String::deserialize(d)?;
+        let marker = match s.to_lowercase().as_str() {
+            "dot" => Marker::Dot,
+            "block" => Marker::Block,
+            "bar" => Marker::Bar,
+            "braille" => Marker::Braille,
+            "halfblock" => Marker::HalfBlock,
+            _ => {
+                return Err(serde::de::Error::custom(format!(
+                    "unknown marker \"{s}\", expected one of: dot, block, bar, braille, halfblock"
+                )))
+            }
+        };
+        Ok(marker)
+    }
+}
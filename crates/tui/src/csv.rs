@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+
+pub const SEP: &str = ",";
+
+/// Quote field if it contains separator or quotes
+pub fn field(field: &str) -> String {
+    if field.contains(SEP) || field.contains('"') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split CSV line into fields, handling quoted fields
+pub fn split(line: &str) -> Result<Vec<String>> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(anyhow!("unclosed quote in entry"));
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(line: &str) -> Vec<String> {
+        split(line).unwrap()
+    }
+
+    #[test]
+    fn plain_fields() {
+        assert_eq!(fields("a,b c, d"), ["a", "b c", " d"]);
+        assert_eq!(fields(""), [""]);
+    }
+
+    #[test]
+    fn trailing_comma_is_empty_field() {
+        assert_eq!(fields("a,b,"), ["a", "b", ""]);
+        assert_eq!(fields(",,"), ["", "", ""]);
+    }
+
+    #[test]
+    fn quoted_fields() {
+        assert_eq!(fields("\"a,b\",c"), ["a,b", "c"]);
+        assert_eq!(fields("a, \"b\""), ["a", "b"]);
+        // quote inside unquoted field is kept
+        assert_eq!(fields("a\"b,c"), ["a\"b", "c"]);
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        assert_eq!(fields("\"say \"\"hi\"\"\",x"), ["say \"hi\"", "x"]);
+        assert_eq!(fields("\"\"\"\""), ["\""]);
+    }
+
+    #[test]
+    fn unclosed_quote() {
+        assert!(split("\"a,b").is_err());
+        assert!(split("a,\"b\"\"").is_err());
+    }
+
+    #[test]
+    fn field_round_trip() {
+        for s in ["plain", "a,b", "say \"hi\"", "\"", ""] {
+            let line = [field(s), field("next")].join(SEP);
+            assert_eq!(fields(&line), [s, "next"]);
+        }
+    }
+}
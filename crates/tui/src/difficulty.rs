@@ -1,17 +1,42 @@
 use std::{fmt::Display, str::FromStr, time::Duration};
 
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use lib::{GameOptions, MoveTo, Pos};
+
 use super::app::{dur2fps, fps};
 
+/// Fastest snake, also for custom difficulty
+const MAX_MOVES_PER_SECOND: u64 = 100;
+const MAX_INITIAL_LENGTH: usize = 20;
+const MAX_GROWTH: usize = 10;
+const MAX_GROWTH_DELAY: usize = 20;
+
 #[derive(Debug, Default, Clone)]
 pub struct Difficulty {
     pub prev: DifficultyKind,
     pub kind: DifficultyKind,
+    /// Rules of [`DifficultyKind::Custom`]
+    pub custom: CustomRules,
     pub fps: DifficultyFps,
 }
 
 impl Difficulty {
-    pub fn update_fps(&mut self) {
-        self.fps = self.kind.to_fps()
+    /// Set speed of snake, which ate `food`
+    pub fn update_fps(&mut self, food: usize) {
+        let speed = match self.kind.moves_per_second() {
+            Some(speed) => speed,
+            None => self.custom.moves_per_second(food),
+        };
+        self.fps = DifficultyFps(fps(speed));
+    }
+    /// Mode under which scores are recorded
+    pub fn mode(&self) -> GameMode {
+        match self.kind {
+            DifficultyKind::Custom => GameMode::Custom(self.custom),
+            _ => GameMode::Classic,
+        }
     }
 }
 
@@ -26,7 +51,9 @@ impl DifficultyFps {
 
 impl Default for DifficultyFps {
     fn default() -> Self {
-        DifficultyKind::default().to_fps()
+        Self(fps(DifficultyKind::default()
+            .moves_per_second()
+            .unwrap_or_default()))
     }
 }
 
@@ -36,15 +63,17 @@ impl Display for DifficultyFps {
     }
 }
 
-pub const DIFFICULTIES: [DifficultyKind; 5] = [
+pub const DIFFICULTIES: [DifficultyKind; 6] = [
     DifficultyKind::Easy,
     DifficultyKind::Normal,
     DifficultyKind::Medium,
     DifficultyKind::Hard,
     DifficultyKind::Impossible,
+    DifficultyKind::Custom,
 ];
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DifficultyKind {
     Easy,
     #[default]
@@ -53,19 +82,23 @@ pub enum DifficultyKind {
     Hard,
     Impossible,
     Secret,
+    /// Speed and rules are set by player, see [`CustomRules`]
+    Custom,
 }
 
 impl DifficultyKind {
-    fn to_fps(self) -> DifficultyFps {
-        let f = match self {
+    /// Snake speed. Not set for [`DifficultyKind::Custom`]
+    pub fn moves_per_second(self) -> Option<u64> {
+        let speed = match self {
             Self::Easy => 5,
             Self::Normal => 10,
             Self::Medium => 15,
             Self::Hard => 30,
             Self::Impossible => 60,
-            Self::Secret => 100,
+            Self::Secret => MAX_MOVES_PER_SECOND,
+            Self::Custom => return None,
         };
-        DifficultyFps(fps(f))
+        Some(speed)
     }
     /// Use in selector
     pub fn next(self) -> Self {
@@ -74,19 +107,21 @@ impl DifficultyKind {
             Self::Normal => Self::Medium,
             Self::Medium => Self::Hard,
             Self::Hard => Self::Impossible,
-            Self::Impossible => Self::Easy,
+            Self::Impossible => Self::Custom,
             Self::Secret => Self::Easy,
+            Self::Custom => Self::Easy,
         }
     }
     /// Use in selector
     pub fn prev(self) -> Self {
         match self {
-            Self::Easy => Self::Impossible,
+            Self::Easy => Self::Custom,
             Self::Normal => Self::Easy,
             Self::Medium => Self::Normal,
             Self::Hard => Self::Medium,
             Self::Impossible => Self::Hard,
             Self::Secret => Self::Impossible,
+            Self::Custom => Self::Impossible,
         }
     }
 }
@@ -100,6 +135,7 @@ impl Display for DifficultyKind {
             Self::Hard => "Hard",
             Self::Impossible => "Impossible",
             Self::Secret => "Secret",
+            Self::Custom => "Custom",
         };
         f.pad(s)
     }
@@ -117,8 +153,391 @@ impl FromStr for DifficultyKind {
             "hard" => Self::Hard,
             "impossible" => Self::Impossible,
             "secret" => Self::Secret,
+            "custom" => Self::Custom,
             _ => return Err("unknown difficulty"),
         };
         Ok(d)
     }
 }
+
+/// Rules of the game, scores are compared only within the same mode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum GameMode {
+    /// Snake goes through walls
+    #[default]
+    Classic,
+    /// Custom difficulty, scores are separate for every rules
+    Custom(CustomRules),
+}
+
+impl GameMode {
+    /// Settings of game on field of `size`. Snake is shortened to fit
+    pub fn game_options(self, size: Pos, seed: Option<u64>) -> GameOptions {
+        let options = GameOptions {
+            seed,
+            ..Default::default()
+        };
+        let Self::Custom(rules) = self else {
+            return options;
+        };
+        let (width, height): (usize, usize) = size.into();
+        let direction = rules.direction.into();
+        let line = match direction {
+            MoveTo::Left | MoveTo::Right => width,
+            MoveTo::Up | MoveTo::Down => height,
+        };
+        GameOptions {
+            initial_length: rules
+                .initial_length
+                .min(line)
+                .min((width * height).saturating_sub(1))
+                .max(1),
+            direction,
+            growth: rules.growth,
+            growth_delay: rules.growth_delay,
+            walls_kill: rules.walls_kill,
+            ..options
+        }
+    }
+}
+
+impl Display for GameMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Classic => f.pad("classic"),
+            Self::Custom(rules) => f.pad(&rules.to_string()),
+        }
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "classic" => Ok(Self::Classic),
+            s => s.parse().map(Self::Custom).map_err(|_| "unknown game mode"),
+        }
+    }
+}
+
+impl From<GameMode> for String {
+    fn from(value: GameMode) -> Self {
+        value.to_string()
+    }
+}
+
+impl TryFrom<String> for GameMode {
+    type Error = &'static str;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Speed and rules of custom difficulty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomRules {
+    /// Snake speed at start
+    pub moves_per_second: u64,
+    /// Snake's cells at start
+    pub initial_length: usize,
+    /// Where snake moves at start
+    pub direction: StartDirection,
+    /// Cells added for every food
+    pub growth: usize,
+    /// Moves after eating, before snake starts growing
+    pub growth_delay: usize,
+    /// Snake dies on field's border instead of going through it
+    pub walls_kill: bool,
+    /// How snake speeds up when eating
+    pub speed_up: SpeedUp,
+}
+
+impl Default for CustomRules {
+    fn default() -> Self {
+        Self {
+            moves_per_second: 10,
+            initial_length: 1,
+            direction: StartDirection::default(),
+            growth: 1,
+            growth_delay: 0,
+            walls_kill: false,
+            speed_up: SpeedUp::default(),
+        }
+    }
+}
+
+impl CustomRules {
+    /// Snake speed after eating `food`
+    pub fn moves_per_second(&self, food: usize) -> u64 {
+        let speed = match self.speed_up {
+            SpeedUp::Off => self.moves_per_second,
+            SpeedUp::Linear => self.moves_per_second.saturating_add(food as u64),
+            SpeedUp::Exponential => {
+                (self.moves_per_second as f64 * 1.05f64.powi(food.min(1000) as i32)) as u64
+            }
+        };
+        speed.min(MAX_MOVES_PER_SECOND)
+    }
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_MOVES_PER_SECOND).contains(&self.moves_per_second) {
+            bail!(
+                "moves_per_second should be from 1 to {MAX_MOVES_PER_SECOND}, got {}",
+                self.moves_per_second
+            );
+        }
+        if !(1..=MAX_INITIAL_LENGTH).contains(&self.initial_length) {
+            bail!(
+                "initial_length should be from 1 to {MAX_INITIAL_LENGTH}, got {}",
+                self.initial_length
+            );
+        }
+        if self.growth > MAX_GROWTH {
+            bail!(
+                "growth should be from 0 to {MAX_GROWTH}, got {}",
+                self.growth
+            );
+        }
+        if self.growth_delay > MAX_GROWTH_DELAY {
+            bail!(
+                "growth_delay should be from 0 to {MAX_GROWTH_DELAY}, got {}",
+                self.growth_delay
+            );
+        }
+        Ok(())
+    }
+    /// Change speed by `step`, keeping it valid
+    pub fn add_speed(&mut self, step: i64) {
+        self.moves_per_second = self
+            .moves_per_second
+            .saturating_add_signed(step)
+            .clamp(1, MAX_MOVES_PER_SECOND);
+    }
+    /// Change initial length by `step`, keeping it valid
+    pub fn add_length(&mut self, step: isize) {
+        self.initial_length = self
+            .initial_length
+            .saturating_add_signed(step)
+            .clamp(1, MAX_INITIAL_LENGTH);
+    }
+    /// Change growth by `step`, keeping it valid
+    pub fn add_growth(&mut self, step: isize) {
+        self.growth = self.growth.saturating_add_signed(step).min(MAX_GROWTH);
+    }
+    /// Change growth delay by `step`, keeping it valid
+    pub fn add_growth_delay(&mut self, step: isize) {
+        self.growth_delay = self
+            .growth_delay
+            .saturating_add_signed(step)
+            .min(MAX_GROWTH_DELAY);
+    }
+}
+
+/// Short form, e.g. `12mps-3len-2grow-walls-linear-up-1delay`. Used as mode
+/// of scores
+impl Display for CustomRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let walls = if self.walls_kill { "walls" } else { "wrap" };
+        write!(
+            f,
+            "{}mps-{}len-{}grow-{walls}-{}-{}-{}delay",
+            self.moves_per_second,
+            self.initial_length,
+            self.growth,
+            self.speed_up,
+            self.direction,
+            self.growth_delay
+        )
+    }
+}
+
+impl FromStr for CustomRules {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const ERR: &str = "invalid custom rules";
+
+        let parts: Vec<_> = s.split('-').collect();
+        // direction and delay were added later
+        let (&[speed, length, growth, walls, speed_up], rest) = parts.split_at(parts.len().min(5))
+        else {
+            return Err(ERR);
+        };
+        let (direction, delay) = match rest {
+            [] => ("right", "0delay"),
+            &[direction, delay] => (direction, delay),
+            _ => return Err(ERR),
+        };
+        let number = |s: &str, suffix| s.strip_suffix(suffix)?.parse().ok();
+        let rules = Self {
+            moves_per_second: number(speed, "mps").ok_or(ERR)?,
+            initial_length: number(length, "len").ok_or(ERR)? as usize,
+            growth: number(growth, "grow").ok_or(ERR)? as usize,
+            walls_kill: match walls {
+                "walls" => true,
+                "wrap" => false,
+                _ => return Err(ERR),
+            },
+            speed_up: speed_up.parse()?,
+            direction: direction.parse()?,
+            growth_delay: number(delay, "delay").ok_or(ERR)? as usize,
+        };
+        rules.validate().map_err(|_| ERR)?;
+        Ok(rules)
+    }
+}
+
+/// How snake speed grows with eaten food
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUp {
+    /// Same speed for the whole game
+    #[default]
+    Off,
+    /// One move per second faster for every food
+    Linear,
+    /// 5% faster for every food
+    Exponential,
+}
+
+impl SpeedUp {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Linear, Self::Exponential];
+}
+
+impl Display for SpeedUp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Off => "off",
+            Self::Linear => "linear",
+            Self::Exponential => "exponential",
+        };
+        f.pad(s)
+    }
+}
+
+impl FromStr for SpeedUp {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "linear" => Ok(Self::Linear),
+            "exponential" => Ok(Self::Exponential),
+            _ => Err("unknown speed-up"),
+        }
+    }
+}
+
+/// Direction of snake at start of custom game
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum StartDirection {
+    Left,
+    #[default]
+    Right,
+    Up,
+    Down,
+}
+
+impl StartDirection {
+    pub const ALL: [Self; 4] = [Self::Left, Self::Right, Self::Up, Self::Down];
+}
+
+impl From<StartDirection> for MoveTo {
+    fn from(value: StartDirection) -> Self {
+        match value {
+            StartDirection::Left => Self::Left,
+            StartDirection::Right => Self::Right,
+            StartDirection::Up => Self::Up,
+            StartDirection::Down => Self::Down,
+        }
+    }
+}
+
+impl Display for StartDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Up => "up",
+            Self::Down => "down",
+        };
+        f.pad(s)
+    }
+}
+
+impl FromStr for StartDirection {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            _ => Err("unknown direction"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_round_trip() {
+        let rules = CustomRules {
+            moves_per_second: 12,
+            initial_length: 3,
+            direction: StartDirection::Up,
+            growth: 2,
+            growth_delay: 1,
+            walls_kill: true,
+            speed_up: SpeedUp::Linear,
+        };
+        let mode = GameMode::Custom(rules);
+        assert_eq!(mode.to_string(), "12mps-3len-2grow-walls-linear-up-1delay");
+        assert_eq!(mode.to_string().parse(), Ok(mode));
+        assert_eq!("classic".parse(), Ok(GameMode::Classic));
+    }
+
+    #[test]
+    fn old_mode_has_default_direction_and_delay() {
+        let rules: CustomRules = "12mps-3len-2grow-walls-linear".parse().unwrap();
+        assert_eq!(rules.direction, StartDirection::Right);
+        assert_eq!(rules.growth_delay, 0);
+    }
+
+    #[test]
+    fn invalid_mode() {
+        assert!("12mps-3len-2grow-walls".parse::<CustomRules>().is_err());
+        assert!("12mps-3len-2grow-walls-linear-up"
+            .parse::<CustomRules>()
+            .is_err());
+        assert!("12mps-3len-2grow-walls-linear-up-99delay"
+            .parse::<CustomRules>()
+            .is_err());
+        assert!("0mps-3len-2grow-walls-linear"
+            .parse::<CustomRules>()
+            .is_err());
+    }
+
+    #[test]
+    fn vertical_length_is_limited_by_height() {
+        let rules = CustomRules {
+            initial_length: 20,
+            direction: StartDirection::Down,
+            ..Default::default()
+        };
+        let options = GameMode::Custom(rules).game_options(Pos::new(30, 6), None);
+        assert_eq!(options.initial_length, 6);
+        assert_eq!(options.direction, MoveTo::Down);
+    }
+}
@@ -0,0 +1,312 @@
+use std::{collections::BTreeMap, io::Write, path::PathBuf, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, TimeDelta};
+
+use lib::{CoordType, DeathCause, Pos};
+
+use crate::{
+    config::HistoryConfig,
+    csv,
+    difficulty::{DifficultyKind, GameMode},
+    paths::config_dif,
+    storage,
+};
+
+const FILE: &str = "history.csv";
+const VERSION: u32 = 1;
+const HEADER: [&str; 9] = [
+    "date",
+    "username",
+    "difficulty",
+    "mode",
+    "size",
+    "seed",
+    "score",
+    "duration_ms",
+    "death",
+];
+
+/// Finished game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub date: DateTime<Local>,
+    pub username: String,
+    pub difficulty: DifficultyKind,
+    pub mode: GameMode,
+    pub size: Pos,
+    pub seed: u64,
+    pub score: usize,
+    pub duration: Duration,
+    /// Not set for won games
+    pub death: Option<DeathCause>,
+}
+
+impl Run {
+    fn to_csv(&self) -> String {
+        let (x, y) = self.size.into();
+        let death = match self.death {
+            Some(DeathCause::SelfCollision) => "self_collision",
+            Some(DeathCause::Wall) => "wall",
+            None => "",
+        };
+        [
+            self.date.to_rfc3339_opts(SecondsFormat::Secs, false),
+            csv::field(&self.username),
+            self.difficulty.to_string().to_lowercase(),
+            self.mode.to_string(),
+            format!("{x}x{y}"),
+            self.seed.to_string(),
+            self.score.to_string(),
+            self.duration.as_millis().to_string(),
+            death.to_string(),
+        ]
+        .join(csv::SEP)
+    }
+    fn from_csv(fields: &[String]) -> Result<Self> {
+        let [date, username, difficulty, mode, size, seed, score, duration, death] = fields else {
+            return Err(anyhow!("unexpected elements count in entry"));
+        };
+        let (x, y) = size.split_once('x').context("invalid field size")?;
+        let death = match death.as_str() {
+            "" => None,
+            "self_collision" => Some(DeathCause::SelfCollision),
+            "wall" => Some(DeathCause::Wall),
+            d => return Err(anyhow!("unknown death cause \"{d}\"")),
+        };
+        Ok(Self {
+            date: DateTime::parse_from_rfc3339(date)
+                .context("invalid date")?
+                .with_timezone(&Local),
+            username: username.to_owned(),
+            difficulty: difficulty.parse().map_err(|e: &str| anyhow!(e))?,
+            mode: mode.parse().map_err(|e: &str| anyhow!(e))?,
+            size: Pos::new(
+                x.parse::<CoordType>().context("invalid field size")?,
+                y.parse::<CoordType>().context("invalid field size")?,
+            ),
+            seed: seed.parse().context("invalid seed")?,
+            score: score.parse().context("invalid score")?,
+            duration: Duration::from_millis(duration.parse().context("invalid duration")?),
+            death,
+        })
+    }
+}
+
+/// All recorded runs, oldest first
+pub fn read() -> Result<Vec<Run>> {
+    let _lock = storage::lock(&history_file())?;
+    read_file()
+}
+
+fn read_file() -> Result<Vec<Run>> {
+    let (_, runs) = storage::read(&history_file(), VERSION, |_, fields| Run::from_csv(fields))?;
+    Ok(runs)
+}
+
+/// Add run to the end of history
+pub fn append(run: &Run) -> Result<()> {
+    let file = history_file();
+    let _lock = storage::lock(&file)?;
+    let header = !file.exists();
+
+    let mut f = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file)
+        .context("failed to open history")?;
+    let mut text = String::new();
+    if header {
+        text += &(storage::version_line(VERSION) + "\n");
+        text += &(header_line() + "\n");
+    }
+    text += &(run.to_csv() + "\n");
+    f.write_all(text.as_bytes())
+        .context("failed to write history")
+}
+
+/// Remove runs which are too old or don't fit into limits from config
+pub fn prune(config: &HistoryConfig) -> Result<()> {
+    let _lock = storage::lock(&history_file())?;
+    let mut runs = read_file()?;
+    let len = runs.len();
+    retain_recent(&mut runs, config, Local::now());
+    if runs.len() == len {
+        return Ok(());
+    }
+    save(&runs)
+}
+
+/// Keep runs, which fit into limits from config at time `now`
+fn retain_recent(runs: &mut Vec<Run>, config: &HistoryConfig, now: DateTime<Local>) {
+    if config.max_days > 0 {
+        let since = now - TimeDelta::days(config.max_days.into());
+        runs.retain(|r| r.date >= since);
+    }
+    if config.max_runs > 0 && runs.len() > config.max_runs {
+        runs.drain(..runs.len() - config.max_runs);
+    }
+}
+
+fn save(runs: &[Run]) -> Result<()> {
+    let mut res = header_line() + "\n";
+    for r in runs {
+        res += &(r.to_csv() + "\n");
+    }
+    storage::write(&history_file(), VERSION, &res)
+}
+
+/// Latest `n` runs, newest first
+pub fn last(runs: &[Run], n: usize) -> impl Iterator<Item = &Run> {
+    runs.iter().rev().take(n)
+}
+
+/// Run with highest score for every day
+pub fn best_per_day<'a>(runs: impl IntoIterator<Item = &'a Run>) -> BTreeMap<NaiveDate, &'a Run> {
+    let mut res: BTreeMap<NaiveDate, &Run> = BTreeMap::new();
+    for run in runs {
+        let best = res.entry(run.date.date_naive()).or_insert(run);
+        if run.score > best.score {
+            *best = run;
+        }
+    }
+    res
+}
+
+/// Mean score for every difficulty
+pub fn average_per_difficulty<'a>(
+    runs: impl IntoIterator<Item = &'a Run>,
+) -> BTreeMap<DifficultyKind, f64> {
+    let mut sums: BTreeMap<DifficultyKind, (usize, usize)> = BTreeMap::new();
+    for run in runs {
+        let (sum, count) = sums.entry(run.difficulty).or_default();
+        *sum += run.score;
+        *count += 1;
+    }
+    sums.into_iter()
+        .map(|(d, (sum, count))| (d, sum as f64 / count as f64))
+        .collect()
+}
+
+fn header_line() -> String {
+    HEADER.join(csv::SEP)
+}
+
+fn history_file() -> PathBuf {
+    config_dif().join(FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    /// Run finished at `hour` of day `day` of May 2024, in local time
+    fn run(day: u32, hour: u32, difficulty: DifficultyKind, score: usize) -> Run {
+        Run {
+            date: Local.with_ymd_and_hms(2024, 5, day, hour, 0, 0).unwrap(),
+            username: "bob".to_owned(),
+            difficulty,
+            mode: GameMode::Classic,
+            size: Pos::new(10, 6),
+            seed: 0,
+            score,
+            duration: Duration::from_secs(10),
+            death: Some(DeathCause::SelfCollision),
+        }
+    }
+    fn scores(runs: &[Run]) -> Vec<usize> {
+        runs.iter().map(|r| r.score).collect()
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let mut run = run(1, 12, DifficultyKind::Hard, 5);
+        run.username = "Smith, \"Agent\"".to_owned();
+        run.death = None;
+        let fields = csv::split(&run.to_csv()).unwrap();
+        assert_eq!(fields.len(), HEADER.len());
+        assert_eq!(Run::from_csv(&fields).unwrap(), run);
+    }
+
+    #[test]
+    fn best_per_day_keeps_first_highest() {
+        let runs = [
+            run(1, 10, DifficultyKind::Easy, 3),
+            run(1, 11, DifficultyKind::Easy, 7),
+            run(1, 12, DifficultyKind::Hard, 7),
+            run(3, 12, DifficultyKind::Easy, 1),
+        ];
+        let best = best_per_day(&runs);
+        assert_eq!(best.len(), 2);
+        let first = best.values().next().unwrap();
+        assert_eq!((first.score, first.difficulty), (7, DifficultyKind::Easy));
+        assert_eq!(best.values().last().unwrap().score, 1);
+    }
+
+    #[test]
+    fn average_per_difficulty_of_runs() {
+        let runs = [
+            run(1, 10, DifficultyKind::Easy, 3),
+            run(1, 11, DifficultyKind::Easy, 4),
+            run(2, 12, DifficultyKind::Hard, 10),
+        ];
+        let average = average_per_difficulty(&runs);
+        assert_eq!(
+            average.into_iter().collect::<Vec<_>>(),
+            [(DifficultyKind::Easy, 3.5), (DifficultyKind::Hard, 10.0)]
+        );
+        assert!(average_per_difficulty(&[]).is_empty());
+    }
+
+    #[test]
+    fn prune_by_days() {
+        let mut runs: Vec<_> = (1..=5)
+            .map(|day| run(day, 12, DifficultyKind::Easy, day as usize))
+            .collect();
+        let config = HistoryConfig {
+            max_runs: 0,
+            max_days: 2,
+        };
+        let now = run(5, 18, DifficultyKind::Easy, 0).date;
+        retain_recent(&mut runs, &config, now);
+        assert_eq!(scores(&runs), [4, 5]);
+    }
+
+    #[test]
+    fn prune_by_count() {
+        let mut runs: Vec<_> = (1..=5)
+            .map(|day| run(day, 12, DifficultyKind::Easy, day as usize))
+            .collect();
+        let config = HistoryConfig {
+            max_runs: 3,
+            max_days: 0,
+        };
+        let now = run(5, 18, DifficultyKind::Easy, 0).date;
+        retain_recent(&mut runs, &config, now);
+        assert_eq!(scores(&runs), [3, 4, 5]);
+
+        // zero limits keep everything
+        let config = HistoryConfig {
+            max_runs: 0,
+            max_days: 0,
+        };
+        retain_recent(&mut runs, &config, now);
+        assert_eq!(runs.len(), 3);
+    }
+
+    #[test]
+    fn prune_by_days_and_count() {
+        let mut runs: Vec<_> = (1..=5)
+            .map(|day| run(day, 12, DifficultyKind::Easy, day as usize))
+            .collect();
+        let config = HistoryConfig {
+            max_runs: 1,
+            max_days: 3,
+        };
+        let now = run(5, 18, DifficultyKind::Easy, 0).date;
+        retain_recent(&mut runs, &config, now);
+        assert_eq!(scores(&runs), [5]);
+    }
+}
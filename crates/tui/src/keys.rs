@@ -0,0 +1,469 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use anyhow::{bail, Result};
+use lib::MoveTo;
+use ratatui::crossterm::event::KeyCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Something user can do by pressing a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Pause,
+    Restart,
+    Quit,
+    /// Open difficulty selection, or cancel it
+    Difficulty,
+    /// Switch achivements view
+    Achivements,
+    Debug,
+    Submit,
+    /// Return to previous screen
+    Back,
+    /// Return to main menu from game
+    Menu,
+    /// Show keys and rules
+    Help,
+    /// Select "Secret" difficulty
+    Secret,
+    /// Scroll lists by page
+    PageUp,
+    PageDown,
+    /// Change order of list
+    Sort,
+    /// Change filter of list
+    Filter,
+}
+
+/// Where key is handled. Same key can be bound to different actions in
+/// different contexts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Play,
+    SelectDifficulty,
+    /// Menus and other screens with lists
+    Menu,
+    /// Scrollable help text
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Self; 19] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::Pause,
+        Self::Restart,
+        Self::Quit,
+        Self::Difficulty,
+        Self::Achivements,
+        Self::Debug,
+        Self::Submit,
+        Self::Back,
+        Self::Menu,
+        Self::Help,
+        Self::Secret,
+        Self::PageUp,
+        Self::PageDown,
+        Self::Sort,
+        Self::Filter,
+    ];
+
+    fn contexts(self) -> &'static [KeyContext] {
+        use KeyContext::*;
+
+        match self {
+            // moving is also used for selecting in menus
+            Self::MoveLeft | Self::MoveRight => &[Play, SelectDifficulty, Menu],
+            Self::MoveUp | Self::MoveDown => &[Play, Menu, Help],
+            Self::Pause | Self::Restart | Self::Menu => &[Play],
+            Self::Achivements => &[Play, Menu],
+            Self::Difficulty => &[Play, SelectDifficulty],
+            Self::Quit | Self::Debug | Self::Help => &[Play, SelectDifficulty, Menu, Help],
+            Self::Submit => &[SelectDifficulty, Menu],
+            Self::Back => &[SelectDifficulty, Menu, Help],
+            Self::Secret => &[SelectDifficulty],
+            Self::PageUp | Self::PageDown => &[Menu, Help],
+            Self::Sort | Self::Filter => &[Menu],
+        }
+    }
+    fn name(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "move_left",
+            Self::MoveRight => "move_right",
+            Self::MoveUp => "move_up",
+            Self::MoveDown => "move_down",
+            Self::Pause => "pause",
+            Self::Restart => "restart",
+            Self::Quit => "quit",
+            Self::Difficulty => "difficulty",
+            Self::Achivements => "achivements",
+            Self::Debug => "debug",
+            Self::Submit => "submit",
+            Self::Back => "back",
+            Self::Menu => "menu",
+            Self::Help => "help",
+            Self::Secret => "secret",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::Sort => "sort",
+            Self::Filter => "filter",
+        }
+    }
+    /// Action can be left without keys
+    fn optional(self) -> bool {
+        matches!(self, Self::Secret)
+    }
+}
+
+/// Built-in sets of keybindings
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    #[default]
+    Arrows,
+    Wasd,
+    Vim,
+}
+
+impl Preset {
+    pub const ALL: [Self; 3] = [Self::Arrows, Self::Wasd, Self::Vim];
+
+    fn bindings(self) -> BTreeMap<Action, Vec<Key>> {
+        use KeyCode::*;
+
+        let moves = match self {
+            Self::Arrows => [vec![Left], vec![Right], vec![Up], vec![Down]],
+            Self::Wasd => [
+                vec![Char('a'), Left],
+                vec![Char('d'), Right],
+                vec![Char('w'), Up],
+                vec![Char('s'), Down],
+            ],
+            Self::Vim => [
+                vec![Char('h'), Left],
+                vec![Char('l'), Right],
+                vec![Char('k'), Up],
+                vec![Char('j'), Down],
+            ],
+        };
+        // "a" and "d" are used for moving in wasd
+        let (difficulty, achivements) = match self {
+            Self::Wasd => ('f', 'e'),
+            Self::Arrows | Self::Vim => ('d', 'a'),
+        };
+
+        let [left, right, up, down] = moves;
+        [
+            (Action::MoveLeft, left),
+            (Action::MoveRight, right),
+            (Action::MoveUp, up),
+            (Action::MoveDown, down),
+            (Action::Pause, vec![Esc]),
+            (Action::Restart, vec![Char('r')]),
+            (Action::Quit, vec![Char('q')]),
+            (Action::Difficulty, vec![Char(difficulty)]),
+            (Action::Achivements, vec![Char(achivements)]),
+            (Action::Debug, vec![F(3)]),
+            (Action::Submit, vec![Enter]),
+            (Action::Back, vec![Esc]),
+            (Action::Menu, vec![Char('m')]),
+            (Action::Help, vec![Char('?')]),
+            (Action::Secret, vec![Char('s')]),
+            (Action::PageUp, vec![PageUp]),
+            (Action::PageDown, vec![PageDown]),
+            (Action::Sort, vec![Char('o')]),
+            (Action::Filter, vec![Char('f')]),
+        ]
+        .into_iter()
+        .map(|(action, keys)| (action, keys.into_iter().map(Key).collect()))
+        .collect()
+    }
+}
+
+/// How move keys turn the snake
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlScheme {
+    /// Snake moves in direction of pressed key
+    #[default]
+    Absolute,
+    /// Left and right turn the snake relative to its direction, up and down
+    /// are ignored
+    Relative,
+}
+
+impl ControlScheme {
+    pub const ALL: [Self; 2] = [Self::Absolute, Self::Relative];
+
+    /// New direction of snake after move action
+    pub fn direction(self, action: Action, current: MoveTo) -> Option<MoveTo> {
+        use MoveTo::*;
+
+        match (self, action) {
+            (Self::Absolute, Action::MoveLeft) => Some(Left),
+            (Self::Absolute, Action::MoveRight) => Some(Right),
+            (Self::Absolute, Action::MoveUp) => Some(Up),
+            (Self::Absolute, Action::MoveDown) => Some(Down),
+            (Self::Relative, Action::MoveLeft) => Some(match current {
+                Left => Down,
+                Down => Right,
+                Right => Up,
+                Up => Left,
+            }),
+            (Self::Relative, Action::MoveRight) => Some(match current {
+                Left => Up,
+                Up => Right,
+                Right => Down,
+                Down => Left,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Keybindings settings
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub preset: Preset,
+    pub scheme: ControlScheme,
+    /// Override keys from preset
+    pub bindings: BTreeMap<Action, Vec<Key>>,
+}
+
+/// Resolved mapping from actions to keys
+#[derive(Debug, Clone)]
+pub struct KeyBindings(BTreeMap<Action, Vec<Key>>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(Preset::default().bindings())
+    }
+}
+
+impl KeyBindings {
+    /// Apply custom bindings on top of preset and check for conflicts
+    pub fn new(config: &KeysConfig) -> Result<Self> {
+        let mut bindings = config.preset.bindings();
+        bindings.extend(config.bindings.clone());
+
+        for action in Action::ALL {
+            if !action.optional() && bindings.get(&action).is_none_or(|k| k.is_empty()) {
+                bail!("no keys bound to {}", action.name());
+            }
+        }
+        for (i, (a1, keys1)) in bindings.iter().enumerate() {
+            for (a2, keys2) in bindings.iter().skip(i + 1) {
+                let same_context = a1.contexts().iter().any(|c| a2.contexts().contains(c));
+                if !same_context {
+                    continue;
+                }
+                if let Some(key) = keys1.iter().find(|k| keys2.contains(k)) {
+                    bail!(
+                        "key \"{key}\" is bound to both {} and {}",
+                        a1.name(),
+                        a2.name()
+                    );
+                }
+            }
+        }
+
+        Ok(Self(bindings))
+    }
+    /// Find action for pressed key
+    pub fn action(&self, context: KeyContext, code: KeyCode) -> Option<Action> {
+        self.0
+            .iter()
+            .find(|(a, keys)| a.contexts().contains(&context) && keys.contains(&Key(code)))
+            .map(|(&a, _)| a)
+    }
+    /// All keys of action
+    pub fn keys(&self, action: Action) -> Vec<String> {
+        self.0
+            .get(&action)
+            .map(|k| k.iter().map(|k| k.to_string()).collect())
+            .unwrap_or_default()
+    }
+    /// Main key of action, for pressing it with mouse
+    pub fn code(&self, action: Action) -> Option<KeyCode> {
+        self.0.get(&action).and_then(|k| k.first()).map(|k| k.0)
+    }
+    /// Main key of action, for showing in help
+    pub fn key(&self, action: Action) -> String {
+        self.0
+            .get(&action)
+            .and_then(|k| k.first())
+            .map(|k| k.to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Single key without modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key(KeyCode);
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self.0 {
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Delete".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PageUp".to_string(),
+            KeyCode::PageDown => "PageDown".to_string(),
+            code => format!("{code:?}"),
+        };
+        f.pad(&s)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Self(KeyCode::Char(c)));
+        }
+        let code = match s.to_lowercase().as_str() {
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key \"{s}\"")),
+            },
+        };
+        Ok(Self(code))
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let name = match self.0 {
+            KeyCode::Left => "Left".to_string(),
+            KeyCode::Right => "Right".to_string(),
+            KeyCode::Up => "Up".to_string(),
+            KeyCode::Down => "Down".to_string(),
+            _ => self.to_string(),
+        };
+        s.serialize_str(&name)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(preset: Preset, custom: &[(Action, &[KeyCode])]) -> Result<KeyBindings> {
+        let bindings = custom
+            .iter()
+            .map(|(a, keys)| (*a, keys.iter().copied().map(Key).collect()))
+            .collect();
+        KeyBindings::new(&KeysConfig {
+            preset,
+            bindings,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn presets_have_no_conflicts() {
+        for preset in Preset::ALL {
+            assert!(bindings(preset, &[]).is_ok(), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn same_key_in_one_context_is_conflict() {
+        // both are used in game
+        let err = bindings(Preset::Arrows, &[(Action::Restart, &[KeyCode::Char('q')])])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "key \"q\" is bound to both restart and quit");
+
+        // "a" moves left in wasd
+        let res = bindings(
+            Preset::Wasd,
+            &[(Action::Achivements, &[KeyCode::Char('a')])],
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn same_key_in_different_contexts() {
+        // sorting is only in menus, restart is only in game
+        let keys = bindings(Preset::Arrows, &[(Action::Sort, &[KeyCode::Char('r')])]).unwrap();
+        let key = KeyCode::Char('r');
+        assert_eq!(keys.action(KeyContext::Play, key), Some(Action::Restart));
+        assert_eq!(keys.action(KeyContext::Menu, key), Some(Action::Sort));
+        assert_eq!(keys.action(KeyContext::Help, key), None);
+    }
+
+    #[test]
+    fn every_action_needs_key() {
+        let err = bindings(Preset::Arrows, &[(Action::Quit, &[])])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "no keys bound to quit");
+        assert!(bindings(Preset::Arrows, &[(Action::Secret, &[])]).is_ok());
+    }
+
+    #[test]
+    fn parse_key() {
+        assert_eq!("x".parse(), Ok(Key(KeyCode::Char('x'))));
+        assert_eq!("Space".parse(), Ok(Key(KeyCode::Char(' '))));
+        assert_eq!("pageup".parse(), Ok(Key(KeyCode::PageUp)));
+        assert_eq!("F12".parse(), Ok(Key(KeyCode::F(12))));
+        assert!("F13".parse::<Key>().is_err());
+        assert!("ctrl".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn relative_scheme_turns_snake() {
+        let scheme = ControlScheme::Relative;
+        assert_eq!(
+            scheme.direction(Action::MoveLeft, MoveTo::Right),
+            Some(MoveTo::Up)
+        );
+        assert_eq!(
+            scheme.direction(Action::MoveRight, MoveTo::Right),
+            Some(MoveTo::Down)
+        );
+        assert_eq!(scheme.direction(Action::MoveUp, MoveTo::Right), None);
+    }
+}
@@ -1,14 +1,36 @@
+use clap::Parser;
+
 mod achive;
 mod app;
+mod badges;
+mod cli;
+mod config;
+mod csv;
 mod difficulty;
+mod history;
+mod keys;
+mod paths;
+mod sound;
+mod storage;
 mod strings;
+mod theme;
 mod tui;
 mod widgets;
 
 const PKG_NAME: &str = "snaks";
 
 fn main() -> anyhow::Result<()> {
-    let res = app::App::default().run(&mut tui::init()?, whoami::username());
+    let mut cli = cli::Cli::parse();
+    if let Some(file) = cli.config_file() {
+        paths::set_config_file(file);
+    }
+    if let Some(command) = cli.command.take() {
+        return command.run();
+    }
+
+    let config = config::Config::load()?;
+    strings::set_language(config.ui.language.as_deref())?;
+    let res = app::App::new(cli.options(), config)?.run(&mut tui::init()?);
     tui::restore()?;
     res
 }
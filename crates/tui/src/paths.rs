@@ -0,0 +1,29 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn config_dif() -> PathBuf {
+    CONFIG_DIR
+        .get_or_init(|| {
+            dirs::config_dir()
+                .expect("config dir always exists")
+                .join(crate::PKG_NAME)
+        })
+        .clone()
+}
+
+/// Use another config file. Saved data is still kept in [`config_dif`].
+/// Should be called before config is read
+pub fn set_config_file(file: PathBuf) {
+    let _ = CONFIG_FILE.set(file);
+}
+
+/// Config file set with [`set_config_file`]
+pub fn custom_config_file() -> Option<PathBuf> {
+    CONFIG_FILE.get().cloned()
+}
+
+pub fn error_log_file() -> PathBuf {
+    config_dif().join("error.log")
+}
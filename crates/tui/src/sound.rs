@@ -0,0 +1,93 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::SoundConfig, storage::log_error};
+
+/// Game events which can have sound cues
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SoundEvent {
+    Food,
+    Death,
+    Win,
+    /// Score became higher than best score of player
+    PersonalBest,
+}
+
+/// What is played on event
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cue {
+    /// Terminal bell
+    #[default]
+    Bell,
+    Off,
+    /// Shell command, e.g. `paplay eat.wav`
+    Command(String),
+}
+
+impl Cue {
+    fn play(&self) -> Result<()> {
+        match self {
+            Self::Bell => {
+                let mut out = std::io::stdout();
+                out.write_all(b"\x07")
+                    .and_then(|_| out.flush())
+                    .context("failed to ring bell")
+            }
+            Self::Off => Ok(()),
+            Self::Command(cmd) => {
+                let (shell, flag) = if cfg!(windows) {
+                    ("cmd", "/C")
+                } else {
+                    ("sh", "-c")
+                };
+                let mut child = Command::new(shell)
+                    .args([flag, cmd])
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .with_context(|| format!("failed to run sound command \"{cmd}\""))?;
+                // game should not wait for sound to finish
+                std::thread::spawn(move || child.wait());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Plays cues of events, skipping ones repeated too often
+#[derive(Debug, Default)]
+pub struct Sounds {
+    /// When cue of every event was played last time
+    played: BTreeMap<SoundEvent, Instant>,
+}
+
+impl Sounds {
+    pub fn play(&mut self, config: &SoundConfig, event: SoundEvent) {
+        if !config.enabled {
+            return;
+        }
+        let interval = Duration::from_millis(config.min_interval_ms);
+        let now = Instant::now();
+        if self
+            .played
+            .get(&event)
+            .is_some_and(|t| now.duration_since(*t) < interval)
+        {
+            return;
+        }
+        self.played.insert(event, now);
+        // sound is not important enough to stop the game
+        if let Err(e) = config.cue(event).play() {
+            log_error(&format!("{e:#}"));
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::{fs::File, io::Write, path::Path, time::SystemTime};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    csv,
+    paths::{config_dif, error_log_file},
+};
+
+/// Start of the first line of versioned files
+const VERSION_PREFIX: &str = "#version ";
+
+/// Read CSV file with schema version. Files without version line are of the
+/// first version. Rows which can't be parsed are logged and skipped, and file
+/// is copied to backup before it is rewritten without them. Returns version
+/// of file, or `None` if it does not exist
+pub fn read<T>(
+    path: &Path,
+    current: u32,
+    parse_row: impl Fn(u32, &[String]) -> Result<T>,
+) -> Result<(Option<u32>, Vec<T>)> {
+    if !path.exists() {
+        return Ok((None, vec![]));
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let source = path.display().to_string();
+    let (version, rows, skipped) = parse_rows(&text, &source, current, parse_row)?;
+    if !skipped.is_empty() {
+        skipped.iter().for_each(|msg| log_error(msg));
+        backup(path)?;
+    }
+    Ok((Some(version), rows))
+}
+
+/// Parse CSV text in the same format as [`read`]. `source` is used in errors
+pub fn parse<T>(
+    text: &str,
+    source: &str,
+    current: u32,
+    parse_row: impl Fn(u32, &[String]) -> Result<T>,
+) -> Result<(u32, Vec<T>)> {
+    let (version, rows, skipped) = parse_rows(text, source, current, parse_row)?;
+    skipped.iter().for_each(|msg| log_error(msg));
+    Ok((version, rows))
+}
+
+/// Same as [`parse`], but rows which can't be parsed are returned as messages
+/// instead of being logged
+pub fn parse_rows<T>(
+    text: &str,
+    source: &str,
+    current: u32,
+    parse_row: impl Fn(u32, &[String]) -> Result<T>,
+) -> Result<(u32, Vec<T>, Vec<String>)> {
+    let mut lines = text.lines().enumerate().peekable();
+    let version = match lines
+        .peek()
+        .and_then(|(_, l)| l.strip_prefix(VERSION_PREFIX))
+    {
+        Some(v) => {
+            let v = v
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid version in {source}"))?;
+            lines.next();
+            v
+        }
+        None => 1,
+    };
+    if version > current {
+        bail!(
+            "{source} is saved by newer version of the game (version {version}, supported {current})"
+        );
+    }
+    // column names
+    lines.next();
+
+    let mut skipped = vec![];
+    let rows = lines
+        .filter(|(_, l)| !l.trim().is_empty())
+        .filter_map(|(i, l)| {
+            let row = csv::split(l).and_then(|fields| parse_row(version, &fields));
+            match row {
+                Ok(row) => Some(row),
+                Err(e) => {
+                    skipped.push(format!("{source}:{}: skipped \"{l}\": {e:#}", i + 1));
+                    None
+                }
+            }
+        })
+        .collect();
+    Ok((version, rows, skipped))
+}
+
+/// Copy file to `<name>.bak`, unless backup is already up to date. Keeps rows
+/// which couldn't be parsed, as they are lost on the next write
+fn backup(path: &Path) -> Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".bak");
+    let bak = path.with_file_name(name);
+    if modified(&bak).is_some_and(|b| modified(path).is_some_and(|f| b >= f)) {
+        return Ok(());
+    }
+    std::fs::copy(path, &bak).with_context(|| format!("failed to back up {}", path.display()))?;
+    log_error(&format!(
+        "{} backed up to {}",
+        path.display(),
+        bak.display()
+    ));
+    Ok(())
+}
+
+/// Replace file with version line and `text`
+pub fn write(path: &Path, version: u32, text: &str) -> Result<()> {
+    replace(path, &format!("{}\n{text}", version_line(version)))
+}
+
+/// Replace file with `text`. Data is written to temporary file first, so file
+/// is never left half-written
+pub fn replace(path: &Path, text: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context("failed to create config directory")?;
+    }
+
+    let tmp = path.with_extension("tmp");
+    let write = || -> std::io::Result<()> {
+        let mut f = File::create(&tmp)?;
+        f.write_all(text.as_bytes())?;
+        f.sync_all()?;
+        std::fs::rename(&tmp, path)
+    };
+    write().with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Lock file for exclusive access from several instances of the game. Lock
+/// is released when returned file is dropped. Separate file is locked, because
+/// data file is replaced on write
+pub fn lock(path: &Path) -> Result<File> {
+    std::fs::create_dir_all(config_dif()).context("failed to create config directory")?;
+
+    let lock = path.with_extension("lock");
+    let f = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock)
+        .with_context(|| format!("failed to open {}", lock.display()))?;
+    f.lock()
+        .with_context(|| format!("failed to lock {}", lock.display()))?;
+    Ok(f)
+}
+
+/// Time of last change of file, for noticing changes from other instances
+pub fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Version line for files, which are appended instead of replaced, and for
+/// exported data
+pub fn version_line(version: u32) -> String {
+    format!("{VERSION_PREFIX}{version}")
+}
+
+/// Append message to error log. Errors are ignored, as there is nowhere else
+/// to report them
+pub fn log_error(msg: &str) {
+    let line = format!("{} {msg}\n", chrono::Local::now().to_rfc3339());
+    let _ = std::fs::create_dir_all(config_dif());
+    let _ = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(error_log_file())
+        .and_then(|mut f| f.write_all(line.as_bytes()));
+}
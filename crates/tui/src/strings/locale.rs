@@ -0,0 +1,261 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, RwLock},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::paths::config_dif;
+
+/// Language of strings in code, used when translation is missing
+const FALLBACK: &str = "en";
+
+/// Translations built into the game. Files in `lang` directory of config
+/// override them or add new languages
+const EMBEDDED: [(&str, &str); 2] = [(FALLBACK, ""), ("ru", include_str!("ru.toml"))];
+
+static LOCALE: RwLock<Option<&'static Locale>> = RwLock::new(None);
+/// Languages loaded during the run. Strings are borrowed for the whole run, so
+/// every language is loaded once and kept
+static LOADED: Mutex<Vec<&'static Locale>> = Mutex::new(vec![]);
+
+/// Translated strings of one language
+#[derive(Debug, Default)]
+struct Locale {
+    lang: String,
+    /// Strings by dotted key, e.g. `widgets.menu.help`
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    fn load(lang: &str) -> Result<Option<Self>> {
+        let file = config_dif().join("lang").join(format!("{lang}.toml"));
+        let text = if file.exists() {
+            std::fs::read_to_string(&file)
+                .with_context(|| format!("failed to read {}", file.display()))?
+        } else if let Some((_, text)) = EMBEDDED.iter().find(|(l, _)| *l == lang) {
+            text.to_string()
+        } else {
+            return Ok(None);
+        };
+        let table: toml::Table = toml::from_str(&text)
+            .with_context(|| format!("invalid translation file for \"{lang}\""))?;
+        let mut strings = HashMap::new();
+        flatten(&table, "", &mut strings)
+            .with_context(|| format!("invalid translation file for \"{lang}\""))?;
+        Ok(Some(Self {
+            lang: lang.to_string(),
+            strings,
+        }))
+    }
+}
+
+fn flatten(table: &toml::Table, prefix: &str, res: &mut HashMap<String, String>) -> Result<()> {
+    for (key, value) in table {
+        let key = format!("{prefix}{key}");
+        match value {
+            toml::Value::String(s) => {
+                res.insert(key, s.clone());
+            }
+            toml::Value::Table(t) => flatten(t, &format!("{key}."), res)?,
+            _ => bail!("{key} should be a string or a table"),
+        }
+    }
+    Ok(())
+}
+
+/// Select language of strings. If `lang` is not set, it is taken from
+/// environment. Unknown language from environment falls back to English
+pub fn set_language(lang: Option<&str>) -> Result<()> {
+    let locale = match lang {
+        Some(lang) => match loaded(&normalize(lang))? {
+            Some(locale) => Some(locale),
+            None => bail!("unknown language \"{lang}\""),
+        },
+        // unknown language from environment uses strings in code
+        None => loaded(&env_language())?,
+    };
+    *LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+    Ok(())
+}
+
+/// Language loaded before, or load it now
+fn loaded(lang: &str) -> Result<Option<&'static Locale>> {
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(locale) = loaded.iter().find(|l| l.lang == lang) {
+        return Ok(Some(locale));
+    }
+    let Some(locale) = Locale::load(lang)? else {
+        return Ok(None);
+    };
+    let locale = Box::leak(Box::new(locale));
+    loaded.push(locale);
+    Ok(Some(locale))
+}
+
+/// Check that language can be loaded
+pub fn validate_language(lang: &str) -> Result<()> {
+    match Locale::load(&normalize(lang))? {
+        Some(_) => Ok(()),
+        None => bail!("unknown language \"{lang}\""),
+    }
+}
+
+/// Built-in languages and languages from `lang` directory of config
+pub fn languages() -> Vec<String> {
+    let mut res: Vec<_> = EMBEDDED.iter().map(|(l, _)| l.to_string()).collect();
+    if let Ok(dir) = std::fs::read_dir(config_dif().join("lang")) {
+        let files = dir.filter_map(|e| {
+            let path = e.ok()?.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        });
+        res.extend(files);
+    }
+    res.sort();
+    res.dedup();
+    res
+}
+
+/// Language from locale variables, e.g. `ru` for `ru_RU.UTF-8`
+fn env_language() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|v| std::env::var(v).ok())
+        .find(|v| !v.is_empty())
+        .map(|v| normalize(&v))
+        .filter(|v| v != "c" && v != "posix")
+        .unwrap_or_else(|| FALLBACK.to_string())
+}
+
+fn normalize(lang: &str) -> String {
+    let end = lang.find(['_', '.', '@', '-']).unwrap_or(lang.len());
+    lang[..end].to_lowercase()
+}
+
+fn current() -> Option<&'static Locale> {
+    *LOCALE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Key of string from module of [`super::tr`] and its name
+fn key(module: &str, name: &str) -> String {
+    let module = module.split_once("::tr").map_or("", |(_, m)| m);
+    let module = module.trim_start_matches("::").replace("::", ".");
+    let name = name.trim_start_matches("r#");
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{module}.{name}")
+    }
+}
+
+/// Translation of string, or `default` if there is none
+pub fn get(module: &str, name: &str, default: &'static str) -> &'static str {
+    let Some(locale) = current() else {
+        return default;
+    };
+    match locale.strings.get(&key(module, name)) {
+        Some(s) => s,
+        None => default,
+    }
+}
+
+/// Translation of string with number `n`, in plural form for this number
+pub fn plural(module: &str, name: &str, n: usize, one: &str, other: &str) -> String {
+    let key = key(module, name);
+    let translated = current().and_then(|locale| {
+        let form = plural_form(&locale.lang, n);
+        let strings = &locale.strings;
+        strings
+            .get(&format!("{key}.{form}"))
+            .or_else(|| strings.get(&format!("{key}.other")))
+    });
+    let template = match translated {
+        Some(s) => s,
+        None if n == 1 => one,
+        None => other,
+    };
+    super::format(template, &[("n", n.to_string())])
+}
+
+/// CLDR plural category of number in language
+fn plural_form(lang: &str, n: usize) -> &'static str {
+    match lang {
+        "ru" | "uk" | "be" => match (n % 10, n % 100) {
+            (1, r) if r != 11 => "one",
+            (2..=4, r) if !(12..=14).contains(&r) => "few",
+            _ => "many",
+        },
+        "ja" | "zh" | "ko" => "other",
+        _ if n == 1 => "one",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn russian_plural_forms() {
+        let forms = [1, 2, 5, 11, 12, 21, 22, 25, 101, 111].map(|n| plural_form("ru", n));
+        assert_eq!(
+            forms,
+            ["one", "few", "many", "many", "many", "one", "few", "many", "one", "many"]
+        );
+        assert_eq!(plural_form("ru", 0), "many");
+    }
+
+    #[test]
+    fn other_plural_forms() {
+        assert_eq!(plural_form("en", 1), "one");
+        assert_eq!(plural_form("en", 0), "other");
+        assert_eq!(plural_form("en", 21), "other");
+        assert_eq!(plural_form("ja", 1), "other");
+    }
+
+    #[test]
+    fn key_from_module_path() {
+        assert_eq!(
+            key("snaks::strings::tr::widgets::menu", "help"),
+            "widgets.menu.help"
+        );
+        assert_eq!(key("snaks::strings::tr::finish", "r#type"), "finish.type");
+        assert_eq!(key("snaks::strings::tr", "title"), "title");
+    }
+
+    #[test]
+    fn language_is_loaded_once() {
+        let first = loaded("ru").unwrap().unwrap();
+        let second = loaded("ru").unwrap().unwrap();
+        assert!(std::ptr::eq(first, second));
+        assert!(loaded("xx").unwrap().is_none());
+    }
+
+    #[test]
+    fn normalize_language() {
+        assert_eq!(normalize("ru_RU.UTF-8"), "ru");
+        assert_eq!(normalize("en-US"), "en");
+        assert_eq!(normalize("DE"), "de");
+        assert_eq!(normalize("sr@latin"), "sr");
+    }
+
+    #[test]
+    fn embedded_translations_are_valid() {
+        for (lang, text) in EMBEDDED {
+            let table: toml::Table = toml::from_str(text).unwrap();
+            let mut strings = HashMap::new();
+            flatten(&table, "", &mut strings).unwrap();
+            assert!(lang == FALLBACK || strings.contains_key("widgets.menu.help"));
+        }
+    }
+
+    #[test]
+    fn flatten_rejects_non_strings() {
+        let table: toml::Table = toml::from_str("[a]\nb = 1").unwrap();
+        let err = flatten(&table, "", &mut HashMap::new()).unwrap_err();
+        assert_eq!(err.to_string(), "a.b should be a string or a table");
+    }
+}
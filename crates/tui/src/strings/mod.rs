@@ -1,11 +1,71 @@
-#![allow(non_upper_case_globals)]
+use ratatui::text::Span;
 
+mod locale;
+
+pub use locale::{languages, set_language, validate_language};
+
+/// Functions returning strings in selected language, with English text as
+/// fallback. Strings are looked up by module path and name, e.g.
+/// `widgets.menu.help`
 macro_rules! strings {
     ($($name:ident = $s:literal),* $(,)?) => {
-        $(pub const $name: &str = $s;)*
+        $(pub fn $name() -> &'static str {
+            $crate::strings::locale::get(module_path!(), stringify!($name), $s)
+        })*
     };
 }
 
+/// Like [`strings`], but strings contain number `{n}` and depend on it
+macro_rules! plurals {
+    ($($name:ident = ($one:literal, $other:literal)),* $(,)?) => {
+        $(pub fn $name(n: usize) -> String {
+            $crate::strings::locale::plural(module_path!(), stringify!($name), n, $one, $other)
+        })*
+    };
+}
+
+/// Replace placeholders like `{name}` in translated string
+pub fn format(template: &str, args: &[(&str, String)]) -> String {
+    format_spans(
+        template,
+        args.iter().map(|(k, v)| (*k, Span::raw(v.clone()))),
+    )
+    .into_iter()
+    .map(|s| s.content)
+    .collect()
+}
+
+/// Like [`format`], but placeholders are replaced with styled spans
+pub fn format_spans<'a>(
+    template: &str,
+    args: impl IntoIterator<Item = (&'a str, Span<'a>)>,
+) -> Vec<Span<'a>> {
+    let args: Vec<_> = args.into_iter().collect();
+    let mut res = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        match args.iter().find(|(k, _)| *k == name) {
+            Some((_, span)) => {
+                if start > 0 {
+                    res.push(Span::raw(rest[..start].to_string()));
+                }
+                res.push(span.clone());
+            }
+            // unknown placeholders are left as is
+            None => res.push(Span::raw(rest[..start + len + 1].to_string())),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        res.push(Span::raw(rest.to_string()));
+    }
+    res
+}
+
 pub mod tr {
     pub mod widgets {
         pub mod app {
@@ -14,8 +74,19 @@ pub mod tr {
         pub mod achivements {
             strings! {
                 achivements = "Achivements",
-                achivements_on = "Achivements on",
+                achivements_on = "Achivements on {difficulty}",
+                rank = "#",
+                player = "Player",
+                mode = "Mode",
+                score = "Score",
+                date = "Date",
+                all = "all",
+                sort = "Sort",
+                sort_score = "score",
+                sort_user = "player",
+                sort_date = "date",
             }
+            pub use super::super::common::difficulty;
         }
         pub mod debug {
             strings! {
@@ -30,9 +101,8 @@ pub mod tr {
         pub mod difficulty {
             strings! {
                 select = "Select difficulty",
-                press = "Press",
-                to_cancel = "to cancel",
-                to_select = "to select",
+                to_cancel = "Press {key} to cancel",
+                to_select = "Press {key} to select {difficulty}",
                 game_restart = "Game will restart",
             }
         }
@@ -40,6 +110,136 @@ pub mod tr {
             strings! {
                 fail = "Game Over",
                 win = "Win",
+                score = "Score",
+                length = "Length",
+                duration = "Time",
+                cause = "Cause",
+                self_collision = "bumped into itself",
+                wall = "hit a wall",
+                rank = "Rank",
+                personal_best = "New personal best!",
+                global_best = "New best score!",
+                retry = "Retry",
+                change_difficulty = "Change difficulty",
+            }
+            pub use super::super::common::quit;
+        }
+        pub mod menu {
+            strings! {
+                new_game = "New game",
+                leaderboard = "Leaderboard",
+                stats = "Statistics",
+                exchange = "Import / export",
+                badges = "Badges",
+                player = "Player",
+                settings = "Settings",
+                help = "Help",
+            }
+            pub use super::super::common::{difficulty, quit};
+        }
+        pub mod players {
+            strings! {
+                title = "Select player",
+                new_player = "New player",
+                name = "Name",
+            }
+        }
+        pub mod stats {
+            strings! {
+                title = "Statistics",
+                player = "Player",
+                all = "all",
+                no_games = "No finished games yet",
+                score_over_time = "Score over time",
+                scores = "Scores per difficulty, bin size {size}",
+                games_per_day = "Games per day (best today: {score})",
+                deaths = "Game endings",
+                won = "won",
+            }
+            pub use super::super::common::difficulty;
+            pub use super::finish::{self_collision, wall};
+        }
+        pub mod exchange {
+            strings! {
+                title = "Import / export scores",
+                export_json = "Export to JSON",
+                export_csv = "Export to CSV",
+                import = "Import",
+                file = "File",
+                exported = "Scores exported to {path}",
+            }
+            plurals! {
+                imported = ("{n} score added or improved", "{n} scores added or improved"),
+            }
+        }
+        pub mod settings {
+            strings! {
+                title = "Settings",
+                theme = "Theme",
+                renderer = "Renderer",
+                mode = "Color mode",
+                keys = "Keybindings",
+                controls = "Controls",
+                ui_fps = "UI FPS",
+                sound = "Sound",
+                player = "Player",
+                on = "on",
+                off = "off",
+                language = "Language",
+                mouse = "Mouse",
+                system_user = "(system)",
+                system_language = "(system)",
+            }
+        }
+        pub mod help {
+            strings! {
+                title = "Help",
+                keys = "Keys",
+                rules = "Rules",
+                rule_food = "Eat food to grow, fill the whole field to win",
+                rule_wrap = "Snake goes through walls and appears on the opposite side",
+                rule_reverse = "Snake can't turn back into itself, unless it is a single block",
+                rule_collide = "Game is over when snake bumps into itself",
+                rule_pause = "Game is paused while this help is open, and when terminal loses focus",
+                difficulties = "Difficulties",
+                difficulty_speed = "Difficulty sets snake speed, in moves per second",
+                difficulty_custom = "Custom: your own speed, snake length and direction, growth and its delay, walls and speed-up. Scores are kept separately for every set of rules",
+                difficulty_restart = "Changing difficulty restarts the game",
+            }
+        }
+        pub mod custom {
+            strings! {
+                title = "Custom difficulty",
+                preset = "Preset",
+                new_preset = "(new)",
+                speed = "Moves per second",
+                length = "Start length",
+                direction = "Start direction",
+                growth = "Growth per food",
+                growth_delay = "Growth delay, moves",
+                walls = "Walls",
+                walls_kill = "kill",
+                walls_wrap = "pass through",
+                speed_up = "Speed-up",
+                name = "Preset name",
+                scores = "Scores: {mode}",
+            }
+        }
+        pub mod pause {
+            strings! {
+                resume = "Resume",
+                restart = "Restart",
+                settings = "Settings",
+                menu = "Quit to menu",
+            }
+            pub use super::super::common::pause;
+        }
+        pub mod too_small {
+            strings! {
+                title = "Terminal too small",
+                current = "Current size",
+                required = "Required size",
+                enlarge = "Please enlarge the terminal",
             }
         }
         pub mod info {
@@ -47,25 +247,86 @@ pub mod tr {
             pub use super::super::common::{difficulty, pause};
         }
     }
+    pub mod badges {
+        strings! {
+            title = "Badges",
+            unlocked = "Badge unlocked",
+            locked = "Locked",
+            first_bite = "First bite",
+            first_bite_desc = "Eat first food",
+            hungry = "Hungry",
+            hungry_desc = "Eat 10 food in 30 seconds",
+            half_board = "Half way",
+            half_board_desc = "Fill half of the field",
+            hard_win = "Champion",
+            hard_win_desc = "Win on Hard or harder difficulty",
+            straight = "Straight line",
+            straight_desc = "Move 1000 times without turning",
+        }
+    }
     pub mod keybind {
         strings! {
             r#move = "Move",
             select = "Select",
             submit = "Submit",
             cancel = "Cancel",
+            change = "Change",
+            save = "Save",
+            help = "Help",
+            scroll = "Scroll",
+            close = "Close",
             resume = "Resume",
             achivements_by_user = "Show achivements by user",
             achivements_summary = "Show achivements summary",
             restart = "Restart",
             debug = "Debug",
-            quit = "Quit",
+            back = "Back",
+            menu = "Menu",
+            page = "Page",
+            mode = "Mode",
+            sort = "Sort",
         }
-        pub use super::common::{difficulty, pause};
+        pub use super::common::{difficulty, pause, quit};
     }
     mod common {
         strings! {
             pause = "Pause",
             difficulty = "Difficulty",
+            quit = "Quit",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Stylize;
+
+    use super::*;
+
+    #[test]
+    fn format_replaces_placeholders() {
+        let args = [("n", "3".to_string()), ("name", "bob".to_string())];
+        assert_eq!(format("{name} ate {n} food", &args), "bob ate 3 food");
+        assert_eq!(format("{n}{n}", &args), "33");
+        assert_eq!(format("no placeholders", &args), "no placeholders");
+    }
+
+    #[test]
+    fn format_keeps_unknown_placeholders() {
+        let args = [("n", "3".to_string())];
+        assert_eq!(format("{m} and {n}", &args), "{m} and 3");
+        assert_eq!(format("{n} {unclosed", &args), "3 {unclosed");
+        assert_eq!(format("{}", &args), "{}");
+    }
+
+    #[test]
+    fn format_spans_keeps_style() {
+        let spans = format_spans("Mode: {mode}!", [("mode", "classic".blue())]);
+        assert_eq!(
+            spans,
+            [Span::raw("Mode: "), "classic".blue(), Span::raw("!")]
+        );
+        // no empty spans around placeholder
+        assert_eq!(format_spans("{a}", [("a", "x".bold())]), ["x".bold()]);
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use ratatui::{buffer::Buffer, style::Color};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_THEME: &str = "default";
+
+/// Colors of game field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Theme {
+    pub snake: Color,
+    pub food: Color,
+    pub border: Color,
+}
+
+impl Theme {
+    const BUILTIN: [(&'static str, Self); 4] = [
+        (
+            DEFAULT_THEME,
+            Self {
+                snake: Color::Green,
+                food: Color::Red,
+                border: Color::Cyan,
+            },
+        ),
+        (
+            "solarized",
+            Self {
+                snake: Color::from_u32(0x859900),
+                food: Color::from_u32(0xdc322f),
+                border: Color::from_u32(0x268bd2),
+            },
+        ),
+        (
+            "high-contrast",
+            Self {
+                snake: Color::White,
+                food: Color::LightYellow,
+                border: Color::White,
+            },
+        ),
+        // Okabe-Ito palette
+        (
+            "colorblind",
+            Self {
+                snake: Color::from_u32(0x0072b2),
+                food: Color::from_u32(0xe69f00),
+                border: Color::from_u32(0x56b4e9),
+            },
+        ),
+    ];
+
+    /// Find theme by name, first in user-defined themes, then in built-in
+    pub fn find(name: &str, custom: &BTreeMap<String, Self>) -> Result<Self> {
+        if let Some(theme) = custom.get(name) {
+            return Ok(*theme);
+        }
+        if let Some((_, theme)) = Self::BUILTIN.iter().find(|(n, _)| *n == name) {
+            return Ok(*theme);
+        }
+        bail!(
+            "unknown theme \"{name}\", available: {}",
+            Self::names(custom).join(", ")
+        )
+    }
+    /// Names of built-in and user-defined themes
+    pub fn names(custom: &BTreeMap<String, Self>) -> Vec<String> {
+        let mut names: Vec<_> = Self::BUILTIN.iter().map(|(n, _)| n.to_string()).collect();
+        for name in custom.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::BUILTIN[0].1
+    }
+}
+
+/// How UI is drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    #[default]
+    Color,
+    /// No colors, snake and food are drawn with different symbols
+    Mono,
+    /// Same as [`ColorMode::Mono`], but only ASCII symbols are used
+    Ascii,
+}
+
+impl ColorMode {
+    pub const ALL: [Self; 3] = [Self::Color, Self::Mono, Self::Ascii];
+
+    /// Apply `NO_COLOR` environment variable, see <https://no-color.org>
+    pub fn with_env(self) -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        match self {
+            Self::Color if no_color => Self::Mono,
+            m => m,
+        }
+    }
+    pub fn colored(self) -> bool {
+        self == Self::Color
+    }
+    /// Remove colors and replace unsupported symbols in already rendered buffer
+    pub fn apply(self, buf: &mut Buffer) {
+        if self.colored() {
+            return;
+        }
+        for cell in &mut buf.content {
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+            cell.underline_color = Color::Reset;
+            if self == Self::Ascii && !cell.symbol().is_ascii() {
+                let s = ascii_symbol(cell.symbol());
+                cell.set_symbol(s);
+            }
+        }
+    }
+}
+
+/// Replacement for non-ASCII symbol
+fn ascii_symbol(s: &str) -> &'static str {
+    match s {
+        "─" | "━" | "═" | "▀" | "▄" => "-",
+        "│" | "┃" | "║" => "|",
+        "←" => "<",
+        "→" => ">",
+        "↑" => "^",
+        "↓" => "v",
+        "█" | "▓" | "▒" | "░" => "#",
+        // other box-drawing symbols, mostly corners
+        s if s.chars().all(|c| ('\u{2500}'..='\u{257f}').contains(&c)) => "+",
+        _ => "?",
+    }
+}
@@ -4,6 +4,7 @@ use anyhow::Result;
 use ratatui::{
     backend::CrosstermBackend,
     crossterm::{
+        event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange},
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
         ExecutableCommand,
     },
@@ -15,10 +16,15 @@ pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 pub fn init() -> Result<Tui> {
     stdout().execute(EnterAlternateScreen)?;
     enable_raw_mode()?;
+    // game is paused when terminal loses focus
+    stdout().execute(EnableFocusChange)?;
     Ok(Terminal::new(CrosstermBackend::new(stdout()))?)
 }
 
 pub fn restore() -> Result<()> {
+    // mouse can be captured by the game
+    stdout().execute(DisableMouseCapture)?;
+    stdout().execute(DisableFocusChange)?;
     stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
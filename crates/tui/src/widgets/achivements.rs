@@ -1,90 +1,204 @@
 use ratatui::{
-    style::Stylize,
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
     text::Line,
-    widgets::{Block, Padding, Paragraph, Widget},
+    widgets::{Block, Padding, Row, StatefulWidget, Table, TableState},
 };
 
 use crate::{
-    achive::{Achivement, AchivementMap},
-    difficulty::DifficultyKind,
+    achive::Achivement,
+    difficulty::{DifficultyKind, GameMode},
+    strings::{format_spans, tr},
 };
 
-#[derive(Debug)]
-pub struct Achivements<'ach> {
-    pub difficulty: DifficultyKind,
-    pub show_achivements_grouped: bool,
-    pub achivements: &'ach [Achivement],
-    pub achivements_map: &'ach AchivementMap,
+/// Order of achivements table
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    /// Best scores first
+    #[default]
+    Score,
+    User,
+    /// Newest scores first
+    Date,
 }
 
-impl<'ach> Widget for Achivements<'ach> {
-    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
-    where
-        Self: Sized,
-    {
-        use crate::strings::tr::widgets::achivements as tr;
+impl SortBy {
+    pub const ALL: [Self; 3] = [Self::Score, Self::User, Self::Date];
 
-        let achivements: Vec<_> = if self.show_achivements_grouped {
-            self.achivements_grouped()
-        } else {
-            self.achivements_by_user()
-        };
+    pub fn name(self) -> &'static str {
+        use tr::widgets::achivements as tr;
 
-        let mut text: Vec<_> = if self.show_achivements_grouped {
-            vec![vec![
-                format!("{} ", tr::achivements_on).into(),
-                self.difficulty.to_string().blue(),
-            ]
-            .into()]
-        } else {
-            vec![vec![tr::achivements.into()].into()]
-        };
-        text.push("".into());
-        text.extend_from_slice(&achivements);
-
-        Paragraph::new(text)
-            .block(Block::new().padding(Padding::uniform(1)))
-            .render(area, buf)
+        match self {
+            Self::Score => tr::sort_score(),
+            Self::User => tr::sort_user(),
+            Self::Date => tr::sort_date(),
+        }
     }
 }
 
+/// Table of achivements with ranks on their difficulty
+#[derive(Debug)]
+pub struct Achivements<'ach> {
+    pub achivements: &'ach [Achivement],
+    /// Achivements of this user are highlighted
+    pub user: &'ach str,
+    pub difficulty: Option<DifficultyKind>,
+    pub mode: Option<GameMode>,
+    pub sort: SortBy,
+    /// Show only necessary columns, for narrow areas
+    pub compact: bool,
+}
+
 impl<'ach> Achivements<'ach> {
-    /// Group achivements by user
-    fn achivements_by_user(&self) -> Vec<Line<'_>> {
-        self.achivements_map
+    /// Filtered and sorted achivements with their ranks
+    pub fn rows(&self) -> Vec<(usize, &'ach Achivement)> {
+        let mut rows: Vec<_> = self
+            .achivements
             .iter()
-            .flat_map(|(user, a)| {
-                let a: Vec<_> = a
-                    .iter()
-                    .map(|a| {
-                        vec![
-                            "  ".into(),
-                            a.difficulty.to_string().blue(),
-                            " ".into(),
-                            a.score.to_string().into(),
-                        ]
-                        .into()
-                    })
-                    .collect();
-                let mut res = vec![vec![user.clone().blue()].into()];
-                res.extend_from_slice(&a);
-                res
+            .filter(|a| {
+                self.difficulty.is_none_or(|d| d == a.difficulty)
+                    && self.mode.is_none_or(|m| m == a.mode)
             })
-            .collect()
+            .map(|a| (self.rank(a), a))
+            .collect();
+        rows.sort_by(|(_, a), (_, b)| {
+            let order = match self.sort {
+                SortBy::Score => b.score.cmp(&a.score),
+                SortBy::User => a.username.cmp(&b.username),
+                SortBy::Date => b.date.cmp(&a.date),
+            };
+            // achivements are unique by user, difficulty and mode
+            order.then_with(|| a.cmp(b))
+        });
+        rows
     }
-    /// Show all achivements on current difficulty
-    fn achivements_grouped(&self) -> Vec<Line<'_>> {
-        self.achivements
-            .iter()
-            .filter(|a| a.difficulty == self.difficulty)
-            .map(|a| {
-                vec![
-                    a.username.to_owned().blue(),
-                    " ".into(),
-                    a.score.to_string().into(),
-                ]
-                .into()
+    /// Place among achivements on the same difficulty and mode
+    fn rank(&self, achivement: &Achivement) -> usize {
+        let better = self.achivements.iter().filter(|a| {
+            a.difficulty == achivement.difficulty
+                && a.mode == achivement.mode
+                && a.score > achivement.score
+        });
+        better.count() + 1
+    }
+    /// Areas of visible rows with their indexes in [`Achivements::rows`],
+    /// when table is scrolled to row `offset`
+    pub fn row_areas(&self, area: Rect, offset: usize) -> Vec<(usize, Rect)> {
+        let inner = self.block().inner(area);
+        let header = if self.compact { 0 } else { 1 };
+        let rows = self.rows().len().saturating_sub(offset);
+        (0..inner.height.saturating_sub(header))
+            .take(rows)
+            .map(|i| {
+                let y = inner.y + header + i;
+                (offset + i as usize, Rect::new(inner.x, y, inner.width, 1))
             })
             .collect()
     }
+    fn block(&self) -> Block<'static> {
+        Block::new()
+            .title(self.title())
+            .padding(Padding::uniform(1))
+    }
+    fn title(&self) -> Line<'static> {
+        use tr::widgets::achivements as tr;
+
+        match self.difficulty {
+            Some(d) if self.compact => {
+                format_spans(tr::achivements_on(), [("difficulty", d.to_string().blue())]).into()
+            }
+            _ => tr::achivements().into(),
+        }
+    }
+}
+
+impl StatefulWidget for Achivements<'_> {
+    type State = TableState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TableState) {
+        use tr::widgets::achivements as tr;
+
+        // difficulty is shown in title, if there is only one. Ranks of
+        // different difficulties are not shown together in small table
+        let show_difficulty = !self.compact || self.difficulty.is_none();
+        let show_rank = !self.compact || self.difficulty.is_some();
+        let show_details = !self.compact;
+
+        let mut header = vec![];
+        let mut widths = vec![];
+        if show_rank {
+            header.push(tr::rank());
+            widths.push(Constraint::Length(4));
+        }
+        header.push(tr::player());
+        widths.push(Constraint::Fill(1));
+        let rows = self.rows();
+        if show_difficulty {
+            header.push(tr::difficulty());
+            // header is not shown in small table
+            let header = if self.compact {
+                0
+            } else {
+                tr::difficulty().chars().count()
+            };
+            let width = rows
+                .iter()
+                .map(|(_, a)| a.difficulty.to_string().len())
+                .chain([header])
+                .max()
+                .unwrap_or_default();
+            widths.push(Constraint::Length(width as u16));
+        }
+        if show_details {
+            header.push(tr::mode());
+            // rules of custom difficulty are longer than other modes
+            let width = rows
+                .iter()
+                .map(|(_, a)| a.mode.to_string().len())
+                .chain([tr::mode().chars().count(), 8])
+                .max()
+                .unwrap_or_default();
+            widths.push(Constraint::Length(width as u16));
+        }
+        header.push(tr::score());
+        widths.push(Constraint::Length(6));
+        if show_details {
+            header.push(tr::date());
+            widths.push(Constraint::Length(10));
+        }
+
+        let rows = rows.into_iter().map(|(rank, a)| {
+            let mut cells = vec![];
+            if show_rank {
+                cells.push(rank.to_string());
+            }
+            cells.push(a.username.clone());
+            if show_difficulty {
+                cells.push(a.difficulty.to_string());
+            }
+            if show_details {
+                cells.push(a.mode.to_string());
+            }
+            cells.push(a.score.to_string());
+            if show_details {
+                let date = a.date.map(|d| d.format("%Y-%m-%d").to_string());
+                cells.push(date.unwrap_or_else(|| "-".to_string()));
+            }
+            let row = Row::new(cells);
+            if a.username == self.user {
+                row.style(Style::new().green().bold())
+            } else {
+                row
+            }
+        });
+
+        let mut table = Table::new(rows, widths)
+            .highlight_style(Style::new().reversed())
+            .block(self.block());
+        if !self.compact {
+            table = table.header(Row::new(header).style(Style::new().blue()));
+        }
+        StatefulWidget::render(table, area, buf, state);
+    }
 }
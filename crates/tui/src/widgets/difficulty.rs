@@ -1,26 +1,53 @@
 use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
     style::Stylize,
-    widgets::{Block, Paragraph, Widget},
+    widgets::{Block, Paragraph, Widget, Wrap},
 };
 
-use crate::difficulty::{DifficultyKind, DIFFICULTIES};
+use crate::{
+    difficulty::{DifficultyKind, DIFFICULTIES},
+    strings::format_spans,
+};
 
 #[derive(Debug)]
 pub struct DifficultySelect {
     pub difficulty: DifficultyKind,
     pub difficulty_changed: bool,
+    pub submit_key: String,
+    pub cancel_key: String,
+}
+
+impl DifficultySelect {
+    /// Areas of difficulties on the line with them, for clicking them.
+    /// Difficulties which don't fit into the line are skipped
+    pub fn difficulty_areas(&self, area: Rect) -> Vec<Rect> {
+        let mut x = area.x;
+        let mut res = vec![];
+        for d in DIFFICULTIES {
+            let width = d.to_string().chars().count() as u16;
+            if x + width > area.right() {
+                break;
+            }
+            res.push(Rect::new(x, area.y + 2, width, 1));
+            x += width + 1;
+        }
+        res
+    }
 }
 
 impl Widget for DifficultySelect {
-    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
         use crate::strings::tr::widgets::difficulty as tr;
 
-        let mut line = vec![tr::select.bold(), ":".into()];
-        for d in DIFFICULTIES {
-            line.push(" ".into());
+        let mut line = vec![];
+        for (i, d) in DIFFICULTIES.into_iter().enumerate() {
+            if i > 0 {
+                line.push(" ".into());
+            }
             if d == self.difficulty {
                 line.push(d.to_string().blue());
             } else {
@@ -29,25 +56,26 @@ impl Widget for DifficultySelect {
         }
         let mut text: Vec<_> = vec![
             "".into(),
+            format!("{}:", tr::select()).bold().into(),
             line.into(),
             "".into(),
-            vec![
-                format!("{} ", tr::press).into(),
-                "Enter".blue(),
-                format!(" {} ", tr::to_select).into(),
-                self.difficulty.to_string().blue(),
-            ]
-            .into(),
-            vec![
-                format!("{} ", tr::press).into(),
-                "d".blue(),
-                format!(" {}", tr::to_cancel).into(),
-            ]
+            format_spans(
+                tr::to_select(),
+                [
+                    ("key", self.submit_key.blue()),
+                    ("difficulty", self.difficulty.to_string().blue()),
+                ],
+            )
             .into(),
+            format_spans(tr::to_cancel(), [("key", self.cancel_key.blue())]).into(),
         ];
         if self.difficulty_changed {
-            text.extend_from_slice(&["".into(), tr::game_restart.into()]);
+            text.extend_from_slice(&["".into(), tr::game_restart().into()]);
         }
-        Paragraph::new(text).block(Block::new()).render(area, buf)
+        // translations can be longer than the area
+        Paragraph::new(text)
+            .block(Block::new())
+            .wrap(Wrap { trim: false })
+            .render(area, buf)
     }
 }
@@ -1,45 +1,139 @@
+use std::time::Duration;
+
 use ratatui::{
-    layout::{Alignment, Rect},
-    style::{Color, Style, Styled},
-    widgets::{Block, Paragraph, Widget},
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
 };
 
+use lib::DeathCause;
+
+/// Summary of finished game with actions
 #[derive(Debug)]
-pub struct Finish {
-    pub state: Option<FinishState>,
+pub struct Finish<'a> {
+    pub summary: &'a GameSummary,
+    pub items: Vec<&'a str>,
+    pub selected: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FinishState {
     Fail,
     Win,
 }
 
-impl Widget for Finish {
-    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
-    where
-        Self: Sized,
-    {
-        use crate::strings::tr::widgets::finish as tr;
+/// Results of finished game
+#[derive(Debug, Clone)]
+pub struct GameSummary {
+    pub state: FinishState,
+    pub score: usize,
+    pub length: usize,
+    pub duration: Duration,
+    pub death: Option<DeathCause>,
+    /// Place on leaderboard of current difficulty
+    pub rank: usize,
+    pub best: Option<NewBest>,
+}
 
-        let Some(state) = self.state else { return };
+/// Which record was beaten
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewBest {
+    Personal,
+    Global,
+}
 
-        const W: u16 = 25;
-        const H: u16 = 5;
-        const W2: u16 = W / 2 + 1;
-        const H2: u16 = H / 2 + 1;
-        let rect_fit = Rect::new(area.x - W2, area.y - H2, W, H);
+impl Finish<'_> {
+    const WIDTH: u16 = 36;
+
+    fn color(&self) -> Color {
+        match self.summary.state {
+            FinishState::Fail => Color::Red,
+            FinishState::Win => Color::Green,
+        }
+    }
+    fn lines(&self) -> Vec<Line<'static>> {
+        use crate::strings::tr::widgets::finish as tr;
 
-        let (text, color) = match state {
-            FinishState::Fail => (tr::fail, Color::Red),
-            FinishState::Win => (tr::win, Color::Green),
+        let s = self.summary;
+        let color = self.color();
+        let title = match s.state {
+            FinishState::Fail => tr::fail(),
+            FinishState::Win => tr::win(),
         };
-        let style = Style::new().fg(color);
 
-        let text: Vec<_> = vec![vec![].into(), vec![text.set_style(style)].into()];
+        let secs = s.duration.as_secs();
+        let mut stats = vec![
+            (tr::score(), s.score.to_string()),
+            (tr::length(), s.length.to_string()),
+            (tr::duration(), format!("{}:{:02}", secs / 60, secs % 60)),
+        ];
+        if let Some(death) = s.death {
+            let cause = match death {
+                DeathCause::SelfCollision => tr::self_collision(),
+                DeathCause::Wall => tr::wall(),
+            };
+            stats.push((tr::cause(), cause.to_string()));
+        }
+        stats.push((tr::rank(), format!("#{}", s.rank)));
+
+        let mut text: Vec<Line> = vec![title.fg(color).bold().into(), "".into()];
+        text.extend(
+            stats
+                .into_iter()
+                .map(|(name, value)| vec![format!("{name}: ").into(), value.blue()].into()),
+        );
+        match s.best {
+            Some(NewBest::Global) => text.push(tr::global_best().yellow().bold().into()),
+            Some(NewBest::Personal) => text.push(tr::personal_best().green().bold().into()),
+            None => {}
+        }
+        text.push("".into());
+        text.extend(self.items.iter().enumerate().map(|(i, &item)| {
+            if i == self.selected {
+                format!("> {item} <").blue().bold().into()
+            } else {
+                item.to_string().into()
+            }
+        }));
+        text
+    }
+    /// Area of bordered box in the center
+    fn box_area(&self, area: Rect, lines: usize) -> Rect {
+        let height = lines as u16 + 2;
+        let [area] = Layout::horizontal([Constraint::Length(Self::WIDTH)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        area
+    }
+    /// Areas of items, for clicking them
+    pub fn item_areas(&self, area: Rect) -> Vec<Rect> {
+        let lines = self.lines().len();
+        let inner = Block::bordered().inner(self.box_area(area, lines));
+        let first = lines - self.items.len();
+        (0..self.items.len())
+            .map(|i| Rect::new(inner.x, inner.y + (first + i) as u16, inner.width, 1))
+            .map(|r| r.intersection(inner))
+            .collect()
+    }
+}
+
+impl Widget for Finish<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let text = self.lines();
+        let area = self.box_area(area, text.len());
+
+        Clear.render(area, buf);
         Paragraph::new(text)
-            .block(Block::bordered().border_style(style))
+            .block(Block::bordered().border_style(Style::new().fg(self.color())))
             .alignment(Alignment::Center)
-            .render(area.clamp(rect_fit), buf)
+            .render(area, buf)
     }
 }
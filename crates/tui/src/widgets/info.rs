@@ -23,18 +23,18 @@ impl Widget for Info {
 
         let mut text = vec![
             vec![
-                format!("{} ", tr::score).blue(),
+                format!("{} ", tr::score()).blue(),
                 format!("{}", self.stats.score).into(),
             ]
             .into(),
             vec![
-                format!("{} ", tr::difficulty).blue(),
+                format!("{} ", tr::difficulty()).blue(),
                 format!("{}", self.difficulty.prev).into(),
             ]
             .into(),
         ];
         if self.show_pause {
-            text.push(tr::pause.yellow().into());
+            text.push(tr::pause().yellow().into());
         }
         Paragraph::new(text).block(Block::new()).render(area, buf)
     }
@@ -0,0 +1,67 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+
+/// Vertical list of items, one of which is selected
+#[derive(Debug)]
+pub struct Menu<'a> {
+    pub title: &'a str,
+    pub items: Vec<&'a str>,
+    pub selected: usize,
+}
+
+impl Menu<'_> {
+    fn lines(&self) -> Vec<Line<'static>> {
+        let mut text: Vec<Line> = vec![self.title.to_string().bold().into(), "".into()];
+        text.extend(self.items.iter().enumerate().map(|(i, &item)| {
+            if i == self.selected {
+                format!("> {item} <").blue().bold().into()
+            } else {
+                item.to_string().into()
+            }
+        }));
+        text
+    }
+    /// Area of text, centered vertically
+    fn text_area(&self, area: Rect) -> Rect {
+        let height = self.items.len() as u16 + 2;
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        area
+    }
+    /// Areas of items, for clicking them
+    pub fn item_areas(&self, area: Rect) -> Vec<Rect> {
+        let area = self.text_area(area);
+        let lines = self.lines();
+        lines[2..]
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let width = (line.width() as u16).min(area.width);
+                let x = area.x + (area.width - width) / 2;
+                Rect::new(x, area.y + 2 + i as u16, width, 1).intersection(area)
+            })
+            .collect()
+    }
+}
+
+impl Widget for Menu<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let text = self.lines();
+        let area = self.text_area(area);
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .render(area, buf)
+    }
+}
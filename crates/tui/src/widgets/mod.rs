@@ -2,10 +2,16 @@ mod achivements;
 mod difficulty;
 mod finish;
 mod info;
+mod menu;
 mod snake;
+mod toast;
+mod too_small;
 
-pub use achivements::Achivements;
+pub use achivements::{Achivements, SortBy};
 pub use difficulty::DifficultySelect;
-pub use finish::{Finish, FinishState};
+pub use finish::{Finish, FinishState, GameSummary, NewBest};
 pub use info::Info;
-pub use snake::SnakeField;
+pub use menu::Menu;
+pub use snake::{SnakeCells, SnakeField, SnakeHalfBlocks};
+pub use toast::Toast;
+pub use too_small::TooSmall;
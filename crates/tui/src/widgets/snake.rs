@@ -1,9 +1,16 @@
-use lib::Pos;
+use lib::{MoveTo, Pos};
 use ratatui::{
-    style::Color,
-    widgets::canvas::{Painter, Shape},
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    widgets::{
+        canvas::{Painter, Shape},
+        Block, Widget,
+    },
 };
 
+use crate::theme::Theme;
+
 #[derive(Debug)]
 pub struct SnakeField {
     snake: Vec<Pos>,
@@ -13,12 +20,12 @@ pub struct SnakeField {
 }
 
 impl SnakeField {
-    pub fn new(snake: Vec<Pos>, food: Pos) -> Self {
+    pub fn new(snake: Vec<Pos>, food: Pos, theme: &Theme) -> Self {
         Self {
             snake,
             food,
-            snake_color: Color::Green,
-            food_color: Color::Red,
+            snake_color: theme.snake,
+            food_color: theme.food,
         }
     }
 }
@@ -41,3 +48,209 @@ impl Shape for SnakeField {
         }
     }
 }
+
+/// Snake field drawn cell by cell. Snake's head shows direction, body shows
+/// turns, and tail is thinner than body
+#[derive(Debug)]
+pub struct SnakeCells<'a> {
+    /// Snake's blocks from tail to head
+    pub snake: Vec<Pos>,
+    pub food: Pos,
+    /// Where head faces when snake is a single block. Otherwise it faces the
+    /// way of the last move, as pending turn is not made yet
+    pub direction: MoveTo,
+    /// Field's size, used to connect blocks across field edges
+    pub size: Pos,
+    pub theme: Theme,
+    /// Use only ASCII symbols
+    pub ascii: bool,
+    pub block: Option<Block<'a>>,
+}
+
+/// Symbols for drawing snake. Every cell is 2 symbols wide, same as in
+/// [`SnakeField`]: first is the cell itself, second connects it to the right
+/// neighbour
+struct Glyphs {
+    food: &'static str,
+    /// Left, right, up, down
+    head: [&'static str; 4],
+    tail: [&'static str; 4],
+    horizontal: &'static str,
+    vertical: &'static str,
+    /// Right-down, left-down, up-right, up-left
+    corners: [&'static str; 4],
+}
+
+impl Glyphs {
+    const UNICODE: Self = Self {
+        food: "●",
+        head: ["◀", "▶", "▲", "▼"],
+        tail: ["╴", "╶", "╵", "╷"],
+        horizontal: "─",
+        vertical: "│",
+        corners: ["╭", "╮", "╰", "╯"],
+    };
+    const ASCII: Self = Self {
+        food: "*",
+        head: ["<", ">", "^", "v"],
+        tail: ["-", "-", "|", "|"],
+        horizontal: "-",
+        vertical: "|",
+        corners: ["+", "+", "+", "+"],
+    };
+
+    fn by_direction(symbols: [&'static str; 4], to: MoveTo) -> &'static str {
+        match to {
+            MoveTo::Left => symbols[0],
+            MoveTo::Right => symbols[1],
+            MoveTo::Up => symbols[2],
+            MoveTo::Down => symbols[3],
+        }
+    }
+    /// Symbol for body block connected to two neighbours
+    fn body(&self, a: MoveTo, b: MoveTo) -> &'static str {
+        use MoveTo::*;
+
+        match (a, b) {
+            (Left, Right) | (Right, Left) => self.horizontal,
+            (Up, Down) | (Down, Up) => self.vertical,
+            (Right, Down) | (Down, Right) => self.corners[0],
+            (Left, Down) | (Down, Left) => self.corners[1],
+            (Up, Right) | (Right, Up) => self.corners[2],
+            (Up, Left) | (Left, Up) => self.corners[3],
+            // can't happen, blocks do not overlap
+            _ => self.horizontal,
+        }
+    }
+}
+
+impl SnakeCells<'_> {
+    fn glyphs(&self) -> &'static Glyphs {
+        if self.ascii {
+            &Glyphs::ASCII
+        } else {
+            &Glyphs::UNICODE
+        }
+    }
+    /// Direction from block `i` to block `j`
+    fn direction(&self, i: usize, j: usize) -> Option<MoveTo> {
+        let (a, b) = (self.snake.get(i)?, self.snake.get(j)?);
+        a.direction_to(*b, self.size)
+    }
+}
+
+impl Widget for SnakeCells<'_> {
+    fn render(mut self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let area = match self.block.take() {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+
+        let mut put = |pos: Pos, symbol: &str, connect_right: bool, style: Style| {
+            let (x, y) = pos.into();
+            let (x, y) = (area.x as usize + x * 2, area.y as usize + y);
+            if x + 1 >= area.right() as usize || y >= area.bottom() as usize {
+                return;
+            }
+            let (x, y) = (x as u16, y as u16);
+            buf.set_string(x, y, symbol, style);
+            let connector = if connect_right {
+                self.glyphs().horizontal
+            } else {
+                " "
+            };
+            buf.set_string(x + 1, y, connector, style);
+        };
+
+        let glyphs = self.glyphs();
+        put(
+            self.food,
+            glyphs.food,
+            false,
+            Style::new().fg(self.theme.food),
+        );
+
+        let style = Style::new().fg(self.theme.snake);
+        let last = self.snake.len().saturating_sub(1);
+        for (i, &pos) in self.snake.iter().enumerate() {
+            // towards tail and towards head
+            let prev = i.checked_sub(1).and_then(|j| self.direction(i, j));
+            let next = self.direction(i, i + 1);
+            let connect_right = [prev, next].contains(&Some(MoveTo::Right));
+
+            let (symbol, style) = match (prev, next) {
+                _ if i == last => {
+                    let to = i
+                        .checked_sub(1)
+                        .and_then(|neck| self.direction(neck, i))
+                        .unwrap_or(self.direction);
+                    (Glyphs::by_direction(glyphs.head, to), style.bold())
+                }
+                (None, Some(next)) => (Glyphs::by_direction(glyphs.tail, next), style),
+                (Some(prev), Some(next)) => (glyphs.body(prev, next), style),
+                (_, None) => (glyphs.horizontal, style),
+            };
+            put(pos, symbol, connect_right, style);
+        }
+    }
+}
+
+/// Snake field where every symbol contains two blocks, one above another. This
+/// makes blocks square
+#[derive(Debug)]
+pub struct SnakeHalfBlocks<'a> {
+    pub snake: Vec<Pos>,
+    pub food: Pos,
+    pub theme: Theme,
+    pub block: Option<Block<'a>>,
+}
+
+impl Widget for SnakeHalfBlocks<'_> {
+    fn render(mut self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let area = match self.block.take() {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+
+        let (width, height) = (area.width as usize, area.height as usize * 2);
+        let mut cells = vec![None; width * height];
+        let mut set = |pos: Pos, color| {
+            let (x, y) = pos.into();
+            if x < width && y < height {
+                cells[y * width + x] = Some(color);
+            }
+        };
+        set(self.food, self.theme.food);
+        for &pos in &self.snake {
+            set(pos, self.theme.snake);
+        }
+
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let (i, j) = (x as usize, y as usize * 2);
+                let (top, bottom) = (cells[j * width + i], cells[(j + 1) * width + i]);
+                let (symbol, style) = match (top, bottom) {
+                    (None, None) => continue,
+                    (Some(t), Some(b)) if t == b => ("█", Style::new().fg(t)),
+                    (Some(t), b) => ("▀", Style::new().fg(t).bg(b.unwrap_or(Color::Reset))),
+                    (None, Some(b)) => ("▄", Style::new().fg(b)),
+                };
+                buf.set_string(area.x + x, area.y + y, symbol, style);
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Stylize,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+/// Short notification in top right corner of area
+#[derive(Debug)]
+pub struct Toast<'a> {
+    pub title: &'a str,
+    pub text: &'a str,
+}
+
+impl Widget for Toast<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        const H: u16 = 3;
+
+        let width = self.title.chars().count().max(self.text.chars().count()) as u16 + 4;
+        let rect = Rect::new(area.right().saturating_sub(width), area.y, width, H);
+        let rect = area.intersection(rect);
+
+        Clear.render(rect, buf);
+        Paragraph::new(self.text.bold())
+            .alignment(Alignment::Center)
+            .block(
+                Block::bordered()
+                    .title(self.title)
+                    .title_alignment(Alignment::Center)
+                    .yellow(),
+            )
+            .render(rect, buf)
+    }
+}
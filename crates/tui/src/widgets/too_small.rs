@@ -0,0 +1,58 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    style::Stylize,
+    widgets::{Paragraph, Widget, Wrap},
+};
+
+use lib::Pos;
+
+/// Shown instead of the game when terminal can't fit game field
+#[derive(Debug)]
+pub struct TooSmall {
+    /// Current terminal size
+    pub size: Pos,
+    /// Minimal terminal size
+    pub required: Pos,
+}
+
+impl Widget for TooSmall {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        use crate::strings::tr::widgets::too_small as tr;
+
+        let fmt_size = |size: Pos| {
+            let (x, y) = size.into();
+            format!("{x}x{y}")
+        };
+        let text: Vec<_> = vec![
+            tr::title().bold().red().into(),
+            "".into(),
+            vec![
+                format!("{}: ", tr::current()).into(),
+                fmt_size(self.size).into(),
+            ]
+            .into(),
+            vec![
+                format!("{}: ", tr::required()).into(),
+                fmt_size(self.required).blue(),
+            ]
+            .into(),
+            "".into(),
+            tr::enlarge().into(),
+        ];
+
+        let height = text.len() as u16;
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(area, buf)
+    }
+}